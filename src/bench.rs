@@ -0,0 +1,139 @@
+use std::alloc::{GlobalAlloc, Layout, System};
+use std::fmt::Write;
+use std::hint::black_box;
+use std::sync::atomic::{AtomicU64, Ordering};
+use std::time::{Duration, Instant};
+
+static ALLOCATIONS: AtomicU64 = AtomicU64::new(0);
+static ALLOCATED_BYTES: AtomicU64 = AtomicU64::new(0);
+
+/// Wraps the system allocator and counts every allocation made through it.
+///
+/// Allocation figures are only meaningful when this is installed with `#[global_allocator]`, as the binary does;
+/// otherwise they read as zero.
+pub struct CountingAllocator;
+
+unsafe impl GlobalAlloc for CountingAllocator {
+    unsafe fn alloc(&self, layout: Layout) -> *mut u8 {
+        ALLOCATIONS.fetch_add(1, Ordering::Relaxed);
+        ALLOCATED_BYTES.fetch_add(layout.size() as u64, Ordering::Relaxed);
+        System.alloc(layout)
+    }
+
+    unsafe fn dealloc(&self, ptr: *mut u8, layout: Layout) {
+        System.dealloc(ptr, layout)
+    }
+
+    unsafe fn realloc(&self, ptr: *mut u8, layout: Layout, new_size: usize) -> *mut u8 {
+        ALLOCATIONS.fetch_add(1, Ordering::Relaxed);
+        ALLOCATED_BYTES.fetch_add(new_size as u64, Ordering::Relaxed);
+        System.realloc(ptr, layout, new_size)
+    }
+}
+
+fn allocation_counts() -> (u64, u64) {
+    (ALLOCATIONS.load(Ordering::Relaxed), ALLOCATED_BYTES.load(Ordering::Relaxed))
+}
+
+/// Wall time over a number of runs, plus the allocations made by an average run.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub struct Stats {
+    pub min: Duration,
+    pub median: Duration,
+    pub max: Duration,
+    pub allocations: u64,
+    pub bytes: u64,
+}
+
+#[derive(Debug)]
+pub struct DayBench {
+    pub day: u32,
+    pub parse: Stats,
+    pub part1: Stats,
+    pub part2: Stats,
+}
+
+impl DayBench {
+    pub fn stages(&self) -> [(&'static str, &Stats); 3] {
+        [("parse", &self.parse), ("part1", &self.part1), ("part2", &self.part2)]
+    }
+}
+
+fn summarise(mut times: Vec<Duration>, allocations: u64, bytes: u64) -> Stats {
+    times.sort();
+    let runs = times.len().max(1) as u64;
+    Stats {
+        min: times.first().copied().unwrap_or_default(),
+        median: times.get(times.len() / 2).copied().unwrap_or_default(),
+        max: times.last().copied().unwrap_or_default(),
+        allocations: allocations / runs,
+        bytes: bytes / runs,
+    }
+}
+
+/// Calls `f` `iterations` times, timing each call separately. Whatever `f` returns is dropped inside the timed region.
+pub fn measure<T>(iterations: usize, mut f: impl FnMut() -> T) -> Stats {
+    let mut times = Vec::with_capacity(iterations);
+    let (allocs_before, bytes_before) = allocation_counts();
+    for _ in 0..iterations {
+        let start = Instant::now();
+        drop(black_box(f()));
+        times.push(start.elapsed());
+    }
+    let (allocs_after, bytes_after) = allocation_counts();
+    summarise(times, allocs_after - allocs_before, bytes_after - bytes_before)
+}
+
+pub fn table(results: &[DayBench]) -> String {
+    let mut out = String::new();
+    writeln!(out, "day stage          min       median          max     allocs        bytes").unwrap();
+    for r in results {
+        for (stage, s) in r.stages() {
+            writeln!(out, "{:02}  {:<5} {:>12.2?} {:>12.2?} {:>12.2?} {:>10} {:>12}",
+                     r.day, stage, s.min, s.median, s.max, s.allocations, s.bytes).unwrap();
+        }
+    }
+    out
+}
+
+pub fn json(results: &[DayBench]) -> String {
+    let days: Vec<String> = results.iter().map(|r| {
+        let stages: Vec<String> = r.stages().iter().map(|(stage, s)| {
+            format!("\"{}\": {{\"min_ns\": {}, \"median_ns\": {}, \"max_ns\": {}, \"allocations\": {}, \"bytes\": {}}}",
+                    stage, s.min.as_nanos(), s.median.as_nanos(), s.max.as_nanos(), s.allocations, s.bytes)
+        }).collect();
+        format!("  {{\"day\": {}, {}}}", r.day, stages.join(", "))
+    }).collect();
+    format!("[\n{}\n]\n", days.join(",\n"))
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+
+    fn ms(n: u64) -> Duration {
+        Duration::from_millis(n)
+    }
+
+    #[test]
+    fn test_summarise() {
+        let stats = summarise(vec![ms(5), ms(1), ms(9), ms(3), ms(4)], 50, 500);
+        assert_eq!(stats, Stats { min: ms(1), median: ms(4), max: ms(9), allocations: 10, bytes: 100 });
+    }
+
+    #[test]
+    fn test_measure() {
+        let mut calls = 0;
+        let stats = measure(3, || calls += 1);
+        assert_eq!(calls, 3);
+        assert!(stats.min <= stats.median && stats.median <= stats.max);
+    }
+
+    #[test]
+    fn test_json() {
+        let s = Stats { min: ms(1), median: ms(2), max: ms(3), allocations: 4, bytes: 5 };
+        let out = json(&[DayBench { day: 7, parse: s, part1: s, part2: s }]);
+        assert!(out.starts_with("[\n  {\"day\": 7, \"parse\": {\"min_ns\": 1000000, \"median_ns\": 2000000, "));
+        assert!(out.ends_with("\"allocations\": 4, \"bytes\": 5}}\n]\n"));
+    }
+}
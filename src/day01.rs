@@ -1,32 +1,283 @@
-fn count_increases(depths: Vec<i32>) -> i32 {
-    depths
-        .windows(2)
-        .fold(0, |acc, w| if w[1] > w[0] { acc + 1 } else { acc })
+use std::cmp::Ordering;
+use std::collections::VecDeque;
+use std::fmt::{Display, Formatter};
+use std::io::{self, BufRead};
+
+use crate::Solution;
+use crate::util::{parse_all, ParseError};
+
+/// How each reading compares with the one a window's length before it.
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq)]
+pub struct Trend {
+    pub increases: usize,
+    pub decreases: usize,
+    pub plateaus: usize,
+    /// The most increases in a row.
+    pub longest_increasing_run: usize,
+    current_run: usize,
+}
+
+impl Trend {
+    fn record(&mut self, ordering: Ordering) {
+        match ordering {
+            Ordering::Greater => {
+                self.increases += 1;
+                self.current_run += 1;
+                self.longest_increasing_run = self.longest_increasing_run.max(self.current_run);
+            }
+            Ordering::Less => {
+                self.decreases += 1;
+                self.current_run = 0;
+            }
+            Ordering::Equal => {
+                self.plateaus += 1;
+                self.current_run = 0;
+            }
+        }
+    }
+}
+
+/// Compares the sums of each pair of overlapping `window`-sized windows as the depths stream past. Two neighbouring
+/// windows share all but their end readings, so that's the same as comparing each depth with the one `window`
+/// readings earlier, and only those last `window` readings need keeping.
+pub fn trend(depths: impl IntoIterator<Item=i32>, window: usize) -> Trend {
+    let mut tracker = TrendTracker::new(window);
+    for depth in depths {
+        tracker.push(depth);
+    }
+    tracker.trend
+}
+
+/// Like [`trend`], but for readings tagged with their sweep as [`read_depths`] gives them, starting afresh with each
+/// new sweep. Returns one trend per sweep.
+pub fn trend_per_sweep(readings: impl IntoIterator<Item=(usize, i32)>, window: usize) -> Vec<Trend> {
+    let mut trends = Vec::new();
+    let mut current: Option<(usize, TrendTracker)> = None;
+    for (sweep, depth) in readings {
+        match &mut current {
+            Some((s, tracker)) if *s == sweep => tracker.push(depth),
+            _ => {
+                if let Some((_, tracker)) = current.take() {
+                    trends.push(tracker.trend);
+                }
+                let mut tracker = TrendTracker::new(window);
+                tracker.push(depth);
+                current = Some((sweep, tracker));
+            }
+        }
+    }
+    trends.extend(current.map(|(_, tracker)| tracker.trend));
+    trends
+}
+
+/// The last `window` readings of a sweep, and the trend so far.
+struct TrendTracker {
+    window: usize,
+    recent: VecDeque<i32>,
+    trend: Trend,
+}
+
+impl TrendTracker {
+    fn new(window: usize) -> TrendTracker {
+        assert!(window > 0, "windows must hold at least one reading");
+        TrendTracker { window, recent: VecDeque::with_capacity(window), trend: Trend::default() }
+    }
+
+    fn push(&mut self, depth: i32) {
+        if self.recent.len() == self.window {
+            let earlier = self.recent.pop_front().unwrap();
+            self.trend.record(depth.cmp(&earlier));
+        }
+        self.recent.push_back(depth);
+    }
+}
+
+fn count_increases(depths: impl IntoIterator<Item=i32>, window: usize) -> usize {
+    trend(depths, window).increases
+}
+
+/// Running sums of every `size` consecutive depths.
+pub fn windowed_sum(depths: impl IntoIterator<Item=i32>, size: usize) -> impl Iterator<Item=i32> {
+    assert!(size > 0, "windows must hold at least one reading");
+    let mut recent = VecDeque::with_capacity(size);
+    let mut sum = 0;
+    depths.into_iter().filter_map(move |depth| {
+        sum += depth;
+        recent.push_back(depth);
+        if recent.len() > size {
+            sum -= recent.pop_front().unwrap();
+        }
+        if recent.len() == size { Some(sum) } else { None }
+    })
+}
+
+/// Reads depths a line at a time, so a log never has to be loaded whole. Each depth comes with the number of the
+/// sweep it belongs to, counting from 0; blank lines between readings start a new sweep.
+pub fn read_depths(reader: impl BufRead) -> impl Iterator<Item=io::Result<(usize, i32)>> {
+    let mut sweep = 0;
+    let mut in_sweep = false;
+    reader.lines().filter_map(move |line| {
+        let line = match line {
+            Ok(line) => line,
+            Err(e) => return Some(Err(e)),
+        };
+        let text = line.trim();
+        if text.is_empty() {
+            if in_sweep {
+                sweep += 1;
+                in_sweep = false;
+            }
+            return None;
+        }
+        in_sweep = true;
+        let depth = text.parse()
+            .map_err(|_| io::Error::new(io::ErrorKind::InvalidData, format!("bad depth {:?}", line)));
+        Some(depth.map(|depth| (sweep, depth)))
+    })
+}
+
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum Direction {
+    Spike,
+    Drop,
+}
+
+/// A reading that strays too far from the readings just before it.
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub struct Anomaly {
+    pub index: usize,
+    pub depth: i32,
+    /// The mean and standard deviation of the window of readings before this one.
+    pub mean: f64,
+    pub stddev: f64,
+    pub direction: Direction,
+}
+
+impl Anomaly {
+    /// How many standard deviations the reading is from the mean.
+    pub fn sigmas(&self) -> f64 {
+        (self.depth as f64 - self.mean).abs() / self.stddev
+    }
+}
+
+#[derive(Clone, Debug, Default, PartialEq)]
+pub struct AnomalyReport {
+    /// Every anomaly, in the order the readings came.
+    pub anomalies: Vec<Anomaly>,
+}
+
+impl AnomalyReport {
+    pub fn spikes(&self) -> impl Iterator<Item=&Anomaly> {
+        self.anomalies.iter().filter(|anomaly| anomaly.direction == Direction::Spike)
+    }
+
+    pub fn drops(&self) -> impl Iterator<Item=&Anomaly> {
+        self.anomalies.iter().filter(|anomaly| anomaly.direction == Direction::Drop)
+    }
+
+    /// The anomaly furthest from its mean, measured in standard deviations.
+    pub fn worst(&self) -> Option<&Anomaly> {
+        self.anomalies.iter().max_by(|a, b| a.sigmas().total_cmp(&b.sigmas()))
+    }
+}
+
+impl Display for AnomalyReport {
+    fn fmt(&self, f: &mut Formatter<'_>) -> std::fmt::Result {
+        writeln!(f, "{} spikes, {} drops", self.spikes().count(), self.drops().count())?;
+        for anomaly in &self.anomalies {
+            writeln!(f, "{:?} at {}: {} against {:.1} ± {:.1}",
+                     anomaly.direction, anomaly.index, anomaly.depth, anomaly.mean, anomaly.stddev)?;
+        }
+        Ok(())
+    }
+}
+
+/// Flags every reading more than `k` standard deviations from the mean of the `window` readings before it. Once the
+/// seabed has been perfectly flat for a whole window, any change at all counts. The window's statistics are kept as
+/// running sums, so the depths can stream past and each one costs the same however wide the window is.
+pub fn detect_anomalies(depths: impl IntoIterator<Item=i32>, window: usize, k: f64) -> AnomalyReport {
+    assert!(window > 0, "windows must hold at least one reading");
+    // Running sums of the window's readings and their squares, kept exact so the variance never drifts
+    let mut recent = VecDeque::with_capacity(window);
+    let (mut sum, mut sum_of_squares) = (0i64, 0i128);
+    let mut anomalies = Vec::new();
+    for (index, depth) in depths.into_iter().enumerate() {
+        if recent.len() == window {
+            let n = window as i128;
+            let mean = sum as f64 / window as f64;
+            let variance = (n * sum_of_squares - (sum as i128).pow(2)) as f64 / (n * n) as f64;
+            let stddev = variance.sqrt();
+            let deviation = depth as f64 - mean;
+            if deviation.abs() > k * stddev {
+                let direction = if deviation > 0.0 { Direction::Spike } else { Direction::Drop };
+                anomalies.push(Anomaly { index, depth, mean, stddev, direction });
+            }
+            let earlier: i32 = recent.pop_front().unwrap();
+            sum -= earlier as i64;
+            sum_of_squares -= (earlier as i128).pow(2);
+        }
+        recent.push_back(depth);
+        sum += depth as i64;
+        sum_of_squares += (depth as i128).pow(2);
+    }
+    AnomalyReport { anomalies }
 }
 
-fn windowed_sum(nums: Vec<i32>, size: usize) -> Vec<i32> {
-    nums.windows(size)
-        .map(|nums| nums.iter().sum())
-        .collect()
+pub struct Day01;
+
+impl Solution for Day01 {
+    const DAY: u32 = 1;
+
+    /// Each sweep is analysed on its own, with no comparisons across the gap between two of them.
+    type Input = Vec<Vec<i32>>;
+    type Answer1 = usize;
+    type Answer2 = usize;
+
+    fn parse(input: &str) -> Result<Vec<Vec<i32>>, ParseError> {
+        parse_all(Self::DAY, input, parse::sweeps)
+    }
+
+    fn part1(sweeps: &Vec<Vec<i32>>) -> usize {
+        sweeps.iter().map(|depths| count_increases(depths.iter().copied(), 1)).sum()
+    }
+
+    fn part2(sweeps: &Vec<Vec<i32>>) -> usize {
+        sweeps.iter().map(|depths| count_increases(depths.iter().copied(), 3)).sum()
+    }
 }
 
 mod parse {
-    use nom::character::complete::{digit1, newline};
-    use nom::combinator::map_res;
+    use nom::character::complete::{i32 as num, line_ending, multispace0, space0};
+    use nom::combinator::recognize;
     use nom::IResult;
-    use nom::multi::separated_list1;
+    use nom::multi::many1;
+    use nom::sequence::{delimited, pair, terminated};
+
+    use crate::util::lines1;
 
-    pub fn digits_as_i32(input: &str) -> IResult<&str, i32> {
-        map_res(digit1, |num| str::parse(num))(input)
+    /// A depth with an optional sign, since calibrated readings can be above the surface.
+    pub fn reading(input: &str) -> IResult<&str, i32> {
+        delimited(space0, num, space0)(input)
     }
 
+    /// One sweep, a reading per line.
     pub(crate) fn depths(input: &str) -> IResult<&str, Vec<i32>> {
-        separated_list1(newline, digits_as_i32)(input)
+        lines1(line_ending, reading)(input)
+    }
+
+    fn blank_lines(input: &str) -> IResult<&str, &str> {
+        recognize(pair(line_ending, many1(terminated(space0, line_ending))))(input)
+    }
+
+    pub(crate) fn sweeps(input: &str) -> IResult<&str, Vec<Vec<i32>>> {
+        terminated(lines1(blank_lines, depths), multispace0)(input)
     }
 }
 
+#[cfg(test)]
 mod test {
-    use crate::util::puzzle_input;
+    use crate::Part;
+    use crate::verify::assert_answer;
 
     use super::*;
 
@@ -38,35 +289,118 @@ mod test {
         assert_eq!(depth_list, [199, 200, 208, 210, 200, 207, 240, 269, 260, 263]);
     }
 
+    #[test]
+    fn test_parse_sweeps() {
+        let (rest, sweeps) = parse::sweeps("-3\n+12  \r\n4\r\n\r\n5\n6\n \n\n7\n\n").unwrap();
+        assert_eq!(rest, "");
+        assert_eq!(sweeps, [vec![-3, 12, 4], vec![5, 6], vec![7]]);
+        let err = Day01::parse("1\n2\n\n3\n4x").unwrap_err();
+        assert_eq!((err.line, err.column, err.expected.as_str()), (5, 2, "a line break"));
+        let err = Day01::parse("1\n--2").unwrap_err();
+        assert_eq!((err.line, err.column, err.expected.as_str()), (2, 1, "a number"));
+    }
+
+    #[test]
+    fn test_sweeps_analysed_separately() {
+        let sweeps = Day01::parse("1\n2\n\n3\n4").unwrap();
+        assert_eq!(Day01::part1(&sweeps), 2);
+        let sweeps = Day01::parse("1\n2\n3\n4\n\n-5\n-1\n0\n-6").unwrap();
+        assert_eq!(Day01::part2(&sweeps), 1);
+    }
+
     #[test]
     fn test_increases() {
         let (_, depth_list) = parse::depths(TEST_INPUT).unwrap();
-        assert_eq!(count_increases(depth_list), 7)
+        assert_eq!(count_increases(depth_list, 1), 7)
     }
 
     #[test]
     fn test_increases_windowed() {
         let (_, depth_list) = parse::depths(TEST_INPUT).unwrap();
-        assert_eq!(count_increases(windowed_sum(depth_list, 3)), 5)
+        assert_eq!(count_increases(depth_list.clone(), 3), 5);
+        assert_eq!(count_increases(windowed_sum(depth_list, 3), 1), 5)
     }
 
     #[test]
-    fn part1() {
-        let input = puzzle_input("01");
-        let (_, depth_list) = parse::depths(&input).unwrap();
-        let increases = count_increases(depth_list);
+    fn test_windowed_sum() {
+        let (_, depth_list) = parse::depths(TEST_INPUT).unwrap();
+        let sums: Vec<i32> = windowed_sum(depth_list, 3).collect();
+        assert_eq!(sums, [607, 618, 618, 617, 647, 716, 769, 792]);
+        assert_eq!(windowed_sum([1, 2], 3).count(), 0);
+    }
+
+    #[test]
+    fn test_trend() {
+        let (_, depth_list) = parse::depths(TEST_INPUT).unwrap();
+        let windowed = trend(depth_list.clone(), 3);
+        assert_eq!((windowed.increases, windowed.decreases, windowed.plateaus), (5, 1, 1));
+        assert_eq!(windowed.longest_increasing_run, 4);
 
-        println!("Number of increases: {}", increases);
-        assert_eq!(1752, increases);
+        let single = trend(depth_list, 1);
+        assert_eq!((single.increases, single.decreases, single.plateaus), (7, 2, 0));
+        assert_eq!(single.longest_increasing_run, 3);
+        assert_eq!(trend([5], 1), Trend::default());
     }
 
     #[test]
-    fn part2() {
-        let input = puzzle_input("01");
-        let (_, depth_list) = parse::depths(&input).unwrap();
-        let increases = count_increases(windowed_sum(depth_list, 3));
+    fn test_detect_anomalies() {
+        let depths = [100, 102, 101, 103, 102, 150, 104, 103, 105, 60, 104];
+        let report = detect_anomalies(depths, 3, 3.0);
+        let flagged: Vec<(usize, Direction)> = report.anomalies.iter().map(|a| (a.index, a.direction)).collect();
+        assert_eq!(flagged, [(5, Direction::Spike), (9, Direction::Drop)]);
+        assert_eq!(report.anomalies[0].mean, 102.0);
+        assert!((report.anomalies[0].stddev - (2.0f64 / 3.0).sqrt()).abs() < 1e-12);
+        assert_eq!(report.worst().unwrap().index, 5);
+        assert_eq!(report.to_string().lines().next(), Some("1 spikes, 1 drops"));
+
+        // The spike widens the windows after it enough to hide the drop
+        assert_eq!(detect_anomalies(depths, 4, 3.0).spikes().count(), 1);
+        assert_eq!(detect_anomalies(depths, 4, 3.0).drops().count(), 0);
+        assert_eq!(detect_anomalies(depths, 20, 3.0), AnomalyReport::default());
+
+        let log: String = depths.iter().map(|d| format!("{}\n", d)).collect();
+        let streamed = read_depths(log.as_bytes()).map(|reading| reading.unwrap().1);
+        assert_eq!(detect_anomalies(streamed, 3, 3.0), report);
+    }
 
-        println!("Number of increases (windowed): {}", increases);
-        assert_eq!(1781, increases);
+    #[test]
+    fn test_flat_seabed() {
+        let report = detect_anomalies([7, 7, 7, 8, 8], 3, 10.0);
+        assert_eq!(report.anomalies.len(), 1);
+        assert_eq!(report.anomalies[0].index, 3);
+        assert!(report.anomalies[0].sigmas().is_infinite());
+    }
+
+    #[test]
+    fn test_read_depths() {
+        let reader = io::BufReader::new(TEST_INPUT.as_bytes());
+        let depths = read_depths(reader).map(|reading| reading.unwrap().1);
+        assert_eq!(trend(depths, 3).increases, 5);
+
+        let mut bad = read_depths("1\nx\n".as_bytes());
+        assert_eq!(bad.next().unwrap().unwrap(), (0, 1));
+        assert_eq!(bad.next().unwrap().unwrap_err().kind(), io::ErrorKind::InvalidData);
+    }
+
+    #[test]
+    fn test_read_sweeps() {
+        let log = "1\r\n-2\r\n+3 \r\n\r\n  \r\n5\r\n4\r\n6\r\n\r\n";
+        let readings: Vec<(usize, i32)> = read_depths(log.as_bytes()).map(Result::unwrap).collect();
+        assert_eq!(readings, [(0, 1), (0, -2), (0, 3), (1, 5), (1, 4), (1, 6)]);
+
+        let trends = trend_per_sweep(readings, 1);
+        assert_eq!(trends.iter().map(|t| t.increases).collect::<Vec<_>>(), [1, 1]);
+        assert_eq!(trends.iter().map(|t| t.decreases).collect::<Vec<_>>(), [1, 1]);
+        assert_eq!(trends.len(), Day01::parse(log).unwrap().len());
+    }
+
+    #[test]
+    fn part1() {
+        assert_answer(&Day01, Part::One);
+    }
+
+    #[test]
+    fn part2() {
+        assert_answer(&Day01, Part::Two);
     }
 }
\ No newline at end of file
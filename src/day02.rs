@@ -1,14 +1,88 @@
+use std::fmt::{Display, Formatter};
+use std::slice;
+
+use crate::Solution;
+use crate::util::{parse_all, ParseError};
+
 #[derive(Debug, PartialEq, Eq)]
 pub enum Command {
     Forward(i32),
+    Back(i32),
     Up(i32),
     Down(i32),
+    Turn(Turn),
+    /// Runs the body the given number of times.
+    Repeat(u32, Vec<Command>),
+}
+
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum Turn {
+    Left,
+    Right,
+}
+
+/// Which way the submarine faces. It sets out facing east, along the course the puzzle measures as distance.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum Heading {
+    North,
+    East,
+    South,
+    West,
+}
+
+impl Heading {
+    pub fn turn(self, turn: Turn) -> Heading {
+        use Heading::*;
+        match (self, turn) {
+            (North, Turn::Right) | (South, Turn::Left) => East,
+            (East, Turn::Right) | (West, Turn::Left) => South,
+            (South, Turn::Right) | (North, Turn::Left) => West,
+            (West, Turn::Right) | (East, Turn::Left) => North,
+        }
+    }
+
+    /// How far one step forward changes the distance and drift.
+    fn step(self) -> (i32, i32) {
+        match self {
+            Heading::North => (0, -1),
+            Heading::East => (1, 0),
+            Heading::South => (0, 1),
+            Heading::West => (-1, 0),
+        }
+    }
 }
 
 #[derive(Debug, PartialEq, Eq)]
 pub struct Position {
     pub depth: i32,
     pub distance: i32,
+    /// How far the submarine has strayed to the right of its starting course.
+    pub drift: i32,
+    pub heading: Heading,
+}
+
+/// A program took the submarine further than its position can record.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub struct Overflow;
+
+impl Display for Overflow {
+    fn fmt(&self, f: &mut Formatter<'_>) -> std::fmt::Result {
+        write!(f, "the submarine's position overflows")
+    }
+}
+
+impl std::error::Error for Overflow {}
+
+/// `n` moved on by `scale * d`, or an error if that doesn't fit.
+fn shift(n: i32, scale: i32, d: i32) -> Result<i32, Overflow> {
+    scale.checked_mul(d).and_then(|d| n.checked_add(d)).ok_or(Overflow)
+}
+
+impl Position {
+    fn travel(self, by: i32) -> Result<Position, Overflow> {
+        let (along, across) = self.heading.step();
+        Ok(Position { distance: shift(self.distance, along, by)?, drift: shift(self.drift, across, by)?, ..self })
+    }
 }
 
 #[derive(Debug, PartialEq, Eq)]
@@ -17,74 +91,187 @@ pub struct AimedPosition {
     pub aim: i32,
 }
 
-pub fn perform_direct(pos: Position, cmd: &Command) -> Position {
+pub fn perform_direct(pos: Position, cmd: &Command) -> Result<Position, Overflow> {
     match cmd {
-        Command::Forward(d) => Position { distance: pos.distance + d, ..pos },
-        Command::Up(d) => Position { depth: pos.depth - d, ..pos },
-        Command::Down(d) => Position { depth: pos.depth + d, ..pos }
+        Command::Forward(d) => pos.travel(*d),
+        Command::Back(d) => pos.travel(-d),
+        Command::Up(d) => Ok(Position { depth: shift(pos.depth, -1, *d)?, ..pos }),
+        Command::Down(d) => Ok(Position { depth: shift(pos.depth, 1, *d)?, ..pos }),
+        Command::Turn(turn) => Ok(Position { heading: pos.heading.turn(*turn), ..pos }),
+        Command::Repeat(..) => run(slice::from_ref(cmd), pos),
     }
 }
 
-pub const INITIAL: Position = Position { depth: 0, distance: 0 };
+pub const INITIAL: Position = Position { depth: 0, distance: 0, drift: 0, heading: Heading::East };
 
-pub fn perform_aimed(state: AimedPosition, cmd: &Command) -> AimedPosition {
+/// Moving backwards with the nose pitched down still brings the submarine up, just as reversing a car down a hill
+/// takes it back uphill.
+pub fn perform_aimed(state: AimedPosition, cmd: &Command) -> Result<AimedPosition, Overflow> {
     match cmd {
-        Command::Forward(d) => AimedPosition {
-            pos: Position {
-                distance: state.pos.distance + d,
-                depth: state.pos.depth + state.aim * d,
-            },
-            ..state
-        },
-        Command::Up(d) => AimedPosition { aim: state.aim - d, ..state },
-        Command::Down(d) => AimedPosition { aim: state.aim + d, ..state }
+        Command::Forward(d) | Command::Back(d) => {
+            let by = if matches!(cmd, Command::Back(_)) { -d } else { *d };
+            let pos = state.pos.travel(by)?;
+            Ok(AimedPosition { pos: Position { depth: shift(pos.depth, state.aim, by)?, ..pos }, ..state })
+        }
+        Command::Up(d) => Ok(AimedPosition { aim: shift(state.aim, -1, *d)?, ..state }),
+        Command::Down(d) => Ok(AimedPosition { aim: shift(state.aim, 1, *d)?, ..state }),
+        Command::Turn(_) => Ok(AimedPosition { pos: perform_direct(state.pos, cmd)?, ..state }),
+        Command::Repeat(..) => run(slice::from_ref(cmd), state),
     }
 }
 
 pub const INITIAL_AIM: AimedPosition = AimedPosition { aim: 0, pos: INITIAL };
 
+/// The ways of reading the commands, as a state each command moves on.
+pub trait Submarine: Sized {
+    fn perform(self, cmd: &Command) -> Result<Self, Overflow>;
+    fn position(&self) -> &Position;
+}
+
+impl Submarine for Position {
+    fn perform(self, cmd: &Command) -> Result<Position, Overflow> {
+        perform_direct(self, cmd)
+    }
+
+    fn position(&self) -> &Position {
+        self
+    }
+}
+
+impl Submarine for AimedPosition {
+    fn perform(self, cmd: &Command) -> Result<AimedPosition, Overflow> {
+        perform_aimed(self, cmd)
+    }
+
+    fn position(&self) -> &Position {
+        &self.pos
+    }
+}
+
+/// Runs a program from `start`, unrolling any repeated blocks, and stopping as soon as the position overflows.
+pub fn run<S: Submarine>(program: &[Command], start: S) -> Result<S, Overflow> {
+    program.iter().try_fold(start, |sub, cmd| match cmd {
+        Command::Repeat(times, body) => (0..*times).try_fold(sub, |sub, _| run(body, sub)),
+        _ => sub.perform(cmd),
+    })
+}
+
+fn answer(sub: Result<impl Submarine, Overflow>) -> String {
+    match sub {
+        Ok(sub) => {
+            let pos = sub.position();
+            (pos.depth as i64 * pos.distance as i64).to_string()
+        }
+        Err(e) => e.to_string(),
+    }
+}
+
+pub struct Day02;
+
+impl Solution for Day02 {
+    const DAY: u32 = 2;
+
+    type Input = Vec<Command>;
+    type Answer1 = String;
+    type Answer2 = String;
+
+    fn parse(input: &str) -> Result<Vec<Command>, ParseError> {
+        parse_all(Self::DAY, input, parse::commands)
+    }
+
+    fn part1(commands: &Vec<Command>) -> String {
+        answer(run(commands, INITIAL))
+    }
+
+    fn part2(commands: &Vec<Command>) -> String {
+        answer(run(commands, INITIAL_AIM))
+    }
+}
+
 pub mod parse {
     use nom::{IResult, Parser};
     use nom::branch::alt;
     use nom::bytes::complete::tag;
-    use nom::character::complete::{digit1, newline};
-    use nom::combinator::map_res;
-    use nom::multi::separated_list1;
-    use nom::sequence::preceded;
+    use nom::character::complete::{char, digit1, line_ending, multispace1, not_line_ending, space0, u32 as num};
+    use nom::combinator::{cut, map_res, value};
+    use nom::multi::{many0, separated_list0};
+    use nom::sequence::{delimited, preceded, separated_pair, tuple};
+
+    use crate::util::lines1;
 
-    use super::Command::{self, Down, Forward, Up};
+    use super::Command::{self, Back, Down, Forward, Repeat, Up};
+    use super::Turn;
 
     pub fn digits_as_i32(input: &str) -> IResult<&str, i32> {
-        map_res(digit1, |num| str::parse(num))(input)
+        map_res(digit1, str::parse)(input)
     }
 
     pub fn forward(input: &str) -> IResult<&str, Command> {
         preceded(tag("forward "),
-                 digits_as_i32.map(Forward))(input)
+                 cut(digits_as_i32.map(Forward)))(input)
     }
 
     pub fn up(input: &str) -> IResult<&str, Command> {
         preceded(tag("up "),
-                 digits_as_i32.map(Up))(input)
+                 cut(digits_as_i32.map(Up)))(input)
     }
 
     pub fn down(input: &str) -> IResult<&str, Command> {
         preceded(tag("down "),
-                 digits_as_i32.map(Down))(input)
+                 cut(digits_as_i32.map(Down)))(input)
+    }
+
+    pub fn back(input: &str) -> IResult<&str, Command> {
+        preceded(tag("back "),
+                 cut(digits_as_i32.map(Back)))(input)
     }
 
     pub fn direction(input: &str) -> IResult<&str, Command> {
-        alt((forward, up, down))(input)
+        alt((forward, back, up, down))(input)
+    }
+
+    pub fn turn(input: &str) -> IResult<&str, Command> {
+        let direction = alt((value(Turn::Left, tag("left")), value(Turn::Right, tag("right"))));
+        preceded(tag("turn "), cut(direction.map(Command::Turn)))(input)
+    }
+
+    fn comment(input: &str) -> IResult<&str, &str> {
+        preceded(char('#'), not_line_ending)(input)
+    }
+
+    /// Any whitespace, line breaks and `#` comments, as padding around a list of commands.
+    fn gap(input: &str) -> IResult<&str, ()> {
+        value((), many0(alt((multispace1, comment))))(input)
+    }
+
+    /// What separates two commands: a gap with at least one line break or comment in it.
+    fn gap1(input: &str) -> IResult<&str, ()> {
+        value((), tuple((space0, alt((line_ending, comment)), gap)))(input)
+    }
+
+    fn block(input: &str) -> IResult<&str, Vec<Command>> {
+        delimited(char('{'), delimited(gap, separated_list0(gap1, command), gap), char('}'))(input)
+    }
+
+    pub fn repeat(input: &str) -> IResult<&str, Command> {
+        preceded(tag("repeat "), cut(separated_pair(num, space0, block)))
+            .map(|(times, body)| Repeat(times, body))
+            .parse(input)
+    }
+
+    pub fn command(input: &str) -> IResult<&str, Command> {
+        alt((direction, turn, repeat))(input)
     }
 
     pub fn commands(input: &str) -> IResult<&str, Vec<Command>> {
-        separated_list1(newline, direction)(input)
+        delimited(gap, lines1(gap1, command), gap)(input)
     }
 }
 
 #[cfg(test)]
 mod test {
-    use crate::util::puzzle_input;
+    use crate::Part;
+    use crate::verify::assert_answer;
 
     use super::*;
 
@@ -101,7 +288,7 @@ mod test {
     #[test]
     fn test_perform_direct() {
         let (_, commands) = super::parse::commands(TEST_INPUT).unwrap();
-        let last = commands.iter().fold(INITIAL, perform_direct);
+        let last = commands.iter().try_fold(INITIAL, perform_direct).unwrap();
 
         assert_eq!(last.depth, 10);
         assert_eq!(last.distance, 15);
@@ -109,30 +296,87 @@ mod test {
 
     #[test]
     fn part1() {
-        let input = puzzle_input("02");
-        let (_, commands) = super::parse::commands(&input).unwrap();
-        let last = commands.iter().fold(INITIAL, perform_direct);
-
-        assert_eq!(last.depth, 738);
-        assert_eq!(last.distance, 2011);
+        assert_answer(&Day02, Part::One);
     }
 
     #[test]
     fn test_perform_aimed() {
         let (_, commands) = super::parse::commands(TEST_INPUT).unwrap();
-        let last = commands.iter().fold(INITIAL_AIM, perform_aimed);
+        let last = commands.iter().try_fold(INITIAL_AIM, perform_aimed).unwrap();
 
         assert_eq!(last.pos.depth, 60);
         assert_eq!(last.pos.distance, 15);
     }
 
+    const DIVE_PLAN: &str = "# Survey the trench, then come back
+down 2
+repeat 3 {
+    forward 4 # one leg
+    repeat 2 { turn right }
+    back 1
+}
+turn left
+forward 5
+";
+
     #[test]
-    fn part2() {
-        let input = puzzle_input("02");
-        let (_, commands) = super::parse::commands(&input).unwrap();
-        let last = commands.iter().fold(INITIAL_AIM, perform_aimed);
+    fn test_parse_extended() {
+        use super::Command::*;
+
+        let (rest, commands) = super::parse::commands(DIVE_PLAN).unwrap();
+        assert_eq!(rest, "");
+        assert_eq!(commands, [
+            Down(2),
+            Repeat(3, vec![Forward(4), Repeat(2, vec![Turn(super::Turn::Right)]), Back(1)]),
+            Turn(super::Turn::Left),
+            Forward(5),
+        ]);
+        assert_eq!(super::parse::commands("repeat 2 {}").unwrap().1, [Repeat(2, vec![])]);
+        assert!(Day02::parse("repeat 2 {\nforward 1\n").is_err());
+        assert!(Day02::parse("turn around").is_err());
+        let err = Day02::parse("forward 5\ndown x").unwrap_err();
+        assert_eq!((err.line, err.column, err.expected.as_str()), (2, 6, "a number"));
+        assert!(Day02::parse("forward 1down 2turn leftrepeat 2{back 1}").is_err());
+        assert!(Day02::parse("repeat 2 { forward 1 back 1 }").is_err());
+        assert_eq!(Day02::parse("repeat 2 { forward 1 } # twice\nup 3").unwrap().len(), 2);
+        assert!(Day02::parse("# nothing to do\n").is_err());
+    }
+
+    #[test]
+    fn test_run() {
+        let (_, commands) = super::parse::commands(DIVE_PLAN).unwrap();
+
+        // Each pass of the loop goes forward 4 and turns about, so backing up 1 carries on the same way
+        let direct = run(&commands, INITIAL).unwrap();
+        assert_eq!(direct, Position { depth: 2, distance: 5, drift: 5, heading: Heading::South });
+
+        // With the aim at 2, every pass dives 8 then climbs 2 again, and the last leg dives 10
+        let aimed = run(&commands, INITIAL_AIM).unwrap();
+        assert_eq!(aimed, AimedPosition { pos: Position { depth: 28, ..direct }, aim: 2 });
+    }
 
-        assert_eq!(last.pos.depth, 727910);
-        assert_eq!(last.pos.distance, 2011);
+    #[test]
+    fn test_classic_commands_unchanged() {
+        let (_, commands) = super::parse::commands(TEST_INPUT).unwrap();
+        assert_eq!(run(&commands, INITIAL), commands.iter().try_fold(INITIAL, perform_direct));
+        assert_eq!(run(&commands, INITIAL_AIM).unwrap().pos.depth, 60);
+    }
+
+    #[test]
+    fn test_overflow() {
+        let far = Day02::parse("repeat 100000 {\n    repeat 100000 {\n        forward 1000\n    }\n}").unwrap();
+        assert_eq!(run(&far, INITIAL), Err(Overflow));
+        assert_eq!(Day02::part1(&far), "the submarine's position overflows");
+
+        // Only the aimed reading multiplies depth by aim
+        let steep = Day02::parse("down 100000\nforward 100000").unwrap();
+        assert_eq!(Day02::part1(&steep), "10000000000");
+        assert_eq!(run(&steep, INITIAL_AIM), Err(Overflow));
+        assert_eq!(run(&Day02::parse("back 2147483647\nback 2").unwrap(), INITIAL), Err(Overflow));
+    }
+
+    #[test]
+    fn part2() {
+        assert_answer(&Day02, Part::Two);
     }
 }
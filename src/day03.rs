@@ -1,4 +1,7 @@
-fn gamma_epsilon(input: &Vec<u32>) -> (u32, u32) {
+use crate::Solution;
+use crate::util::{parse_all, ParseError};
+
+fn gamma_epsilon(input: &[u32]) -> (u32, u32) {
     let digits = input.iter().max().unwrap().next_power_of_two().trailing_zeros();
     let bits = input.len() as u32;
     let mut gamma = 0;
@@ -10,7 +13,7 @@ fn gamma_epsilon(input: &Vec<u32>) -> (u32, u32) {
             .sum();
 
         let d = (ones >= (bits / 2)) as u32;
-        gamma = gamma | d << i;
+        gamma |= d << i;
     }
     let mask = gamma.next_power_of_two() - 1;
     (gamma, gamma ^ mask)
@@ -29,11 +32,7 @@ fn rating(mut vecs: Vec<u32>, most_common: bool) -> u32 {
             .count();
 
         let zeroes = vecs.len() - ones;
-        vecs = vecs
-            .iter()
-            .filter(|&&x| most_common ^ (ones >= zeroes) ^ nth_bit_set(x, b))
-            .copied()
-            .collect();
+        vecs.retain(|&x| most_common ^ (ones >= zeroes) ^ nth_bit_set(x, b));
 
         if vecs.len() < 2 {
             break;
@@ -42,12 +41,38 @@ fn rating(mut vecs: Vec<u32>, most_common: bool) -> u32 {
     vecs[0]
 }
 
+pub struct Day03;
+
+impl Solution for Day03 {
+    const DAY: u32 = 3;
+
+    type Input = Vec<u32>;
+    type Answer1 = u32;
+    type Answer2 = u32;
+
+    fn parse(input: &str) -> Result<Vec<u32>, ParseError> {
+        parse_all(Self::DAY, input, parse::vector_of_binary_u32s)
+    }
+
+    fn part1(vecs: &Vec<u32>) -> u32 {
+        let (gamma, epsilon) = gamma_epsilon(vecs);
+        gamma * epsilon
+    }
+
+    fn part2(vecs: &Vec<u32>) -> u32 {
+        let oxygen = rating(vecs.clone(), true);
+        let co2 = rating(vecs.clone(), false);
+        oxygen * co2
+    }
+}
+
 mod parse {
     use nom::character::complete::newline;
     use nom::character::complete::not_line_ending;
     use nom::combinator::map_res;
     use nom::IResult;
-    use nom::multi::separated_list1;
+
+    use crate::util::lines1;
 
     fn binary_number_as_u32(input: &str) -> IResult<&str, u32> {
         map_res(not_line_ending, |digits|
@@ -55,7 +80,7 @@ mod parse {
     }
 
     pub(crate) fn vector_of_binary_u32s(input: &str) -> IResult<&str, Vec<u32>> {
-        separated_list1(
+        lines1(
             newline,
             binary_number_as_u32,
         )(input)
@@ -65,7 +90,8 @@ mod parse {
 
 #[cfg(test)]
 mod test {
-    use crate::util::puzzle_input;
+    use crate::Part;
+    use crate::verify::assert_answer;
 
     use super::*;
 
@@ -74,7 +100,7 @@ mod test {
 
     #[test]
     fn test_parse() {
-        let (_, input) = parse::vector_of_binary_u32s(&TEST_INPUT).unwrap();
+        let (_, input) = parse::vector_of_binary_u32s(TEST_INPUT).unwrap();
 
         assert_eq!(TEST_INPUT,
                    input.iter()
@@ -85,7 +111,7 @@ mod test {
 
     #[test]
     fn test_gamma_epsilon() {
-        let (_, input) = parse::vector_of_binary_u32s(&TEST_INPUT).unwrap();
+        let (_, input) = parse::vector_of_binary_u32s(TEST_INPUT).unwrap();
         let (gamma, epsilon) = gamma_epsilon(&input);
         assert_eq!(gamma, 22);
         assert_eq!(epsilon, 9);
@@ -93,16 +119,13 @@ mod test {
 
     #[test]
     fn part1() {
-        let input = puzzle_input("03");
-        let (_, vecs) = parse::vector_of_binary_u32s(&input).unwrap();
-        let (gamma, epsilon) = gamma_epsilon(&vecs);
-        assert_eq!(1092896, gamma * epsilon);
+        assert_answer(&Day03, Part::One);
     }
 
     #[test]
     fn test_oxygen_co2() {
         let input = TEST_INPUT;
-        let (_, vecs) = parse::vector_of_binary_u32s(&input).unwrap();
+        let (_, vecs) = parse::vector_of_binary_u32s(input).unwrap();
 
         let oxygen = rating(vecs.clone(), true);
         let co2 = rating(vecs, false);
@@ -112,13 +135,7 @@ mod test {
 
     #[test]
     fn part2() {
-        let input = puzzle_input("03");
-        let (_, vecs) = parse::vector_of_binary_u32s(&input).unwrap();
-
-        let oxygen = rating(vecs.clone(), true);
-        let co2 = rating(vecs, false);
-        assert_eq!(1357, co2);
-        assert_eq!(3443, oxygen);
+        assert_answer(&Day03, Part::Two);
     }
 }
 
@@ -1,3 +1,6 @@
+use crate::Solution;
+use crate::util::{parse_all, ParseError};
+
 #[derive(Debug, PartialEq, Eq, Clone, Copy)]
 pub enum BingoSquare {
     Filled(u32),
@@ -45,36 +48,62 @@ impl BingoBoard {
             }
         }
 
-        return false;
+        false
     }
 }
 
-pub fn call(num: u32, boards: &mut Vec<BingoBoard>) {
+pub fn call(num: u32, boards: &mut [BingoBoard]) {
     for b in boards {
         b.mark(num);
     }
 }
 
-pub fn play_until_winner(nums: Vec<u32>, boards: &mut Vec<BingoBoard>) -> Option<(u32, BingoBoard)> {
+pub fn play_until_winner(nums: Vec<u32>, boards: &mut [BingoBoard]) -> Option<(u32, BingoBoard)> {
     for n in nums {
         call(n, boards);
         if let Some(w) = boards.iter().find(|b| b.is_winner()) {
             return Some((n, w.clone()));
         }
     }
-    return None;
+    None
 }
 
+/// The board that wins last and the number that made it win, or `None` if some board never wins.
 pub fn play_until_last_winner(nums: Vec<u32>, boards: &mut Vec<BingoBoard>) -> Option<(u32, BingoBoard)> {
     for n in nums {
-        if boards.len() == 1 {
-            call(n, boards);
-            return Some((n, boards[0].clone()));
-        }
         call(n, boards);
+        if boards.iter().all(|b| b.is_winner()) {
+            return boards.first().map(|b| (n, b.clone()));
+        }
         boards.retain(|b| !b.is_winner());
     }
-    return None;
+    None
+}
+
+fn score(game: Option<(u32, BingoBoard)>) -> String {
+    game.map_or("no board wins".to_owned(), |(last_num, winner)| (last_num * winner.open_nums()).to_string())
+}
+
+pub struct Day04;
+
+impl Solution for Day04 {
+    const DAY: u32 = 4;
+
+    type Input = (Vec<u32>, Vec<BingoBoard>);
+    type Answer1 = String;
+    type Answer2 = String;
+
+    fn parse(input: &str) -> Result<(Vec<u32>, Vec<BingoBoard>), ParseError> {
+        parse_all(Self::DAY, input, parse::bingo_game)
+    }
+
+    fn part1((calls, boards): &(Vec<u32>, Vec<BingoBoard>)) -> String {
+        score(play_until_winner(calls.clone(), &mut boards.clone()))
+    }
+
+    fn part2((calls, boards): &(Vec<u32>, Vec<BingoBoard>)) -> String {
+        score(play_until_last_winner(calls.clone(), &mut boards.clone()))
+    }
 }
 
 mod parse {
@@ -111,7 +140,8 @@ mod parse {
 
 #[cfg(test)]
 mod test {
-    use crate::util::puzzle_input;
+    use crate::Part;
+    use crate::verify::assert_answer;
 
     use super::*;
 
@@ -161,22 +191,25 @@ mod test {
     }
 
     #[test]
-    fn part1() {
-        let input = puzzle_input("04");
-        let (_, (calls, mut boards)) = parse::bingo_game(&input).unwrap();
-        let (last_num, winner) = play_until_winner(calls, &mut boards).unwrap();
+    fn test_no_winner() {
+        let (calls, boards) = Day04::parse(TEST_INPUT).unwrap();
+        let game = (calls[..3].to_vec(), boards.clone());
+        assert_eq!(Day04::part1(&game), "no board wins");
+        assert_eq!(Day04::part2(&game), "no board wins");
+
+        // The third board wins on 24, but the calls stop before the others do
+        let game = (calls[..12].to_vec(), boards);
+        assert_eq!(Day04::part1(&game), "4512");
+        assert_eq!(Day04::part2(&game), "no board wins");
+    }
 
-        assert_eq!(last_num, 78);
-        assert_eq!(winner.open_nums(), 715)
+    #[test]
+    fn part1() {
+        assert_answer(&Day04, Part::One);
     }
 
     #[test]
     fn part2() {
-        let input = puzzle_input("04");
-        let (_, (calls, mut boards)) = parse::bingo_game(&input).unwrap();
-        let (last_num, winner) = play_until_last_winner(calls, &mut boards).unwrap();
-
-        assert_eq!(last_num, 10);
-        assert_eq!(winner.open_nums(), 298)
+        assert_answer(&Day04, Part::Two);
     }
 }
\ No newline at end of file
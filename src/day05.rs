@@ -1,10 +1,13 @@
 use std::cmp::{max, min};
 use std::collections::HashMap;
 
+use crate::Solution;
+use crate::util::{parse_all, ParseError};
+
 type Point = (i32, i32);
 type Line = (Point, Point);
 
-pub fn record_lines(lines: &Vec<Line>) -> HashMap<Point, i32> {
+pub fn record_lines(lines: &[Line]) -> HashMap<Point, i32> {
     let mut map = HashMap::new();
     for &((x1, y1), (x2, y2)) in lines {
         if x1 == x2 {
@@ -33,8 +36,8 @@ pub fn record_lines(lines: &Vec<Line>) -> HashMap<Point, i32> {
     map
 }
 
-fn without_diagonals(lines: &Vec<Line>) -> Vec<Line> {
-    lines.into_iter()
+fn without_diagonals(lines: &[Line]) -> Vec<Line> {
+    lines.iter()
         .filter(|((x1, y1), (x2, y2))| (x1 == x2) || (y1 == y2))
         .copied()
         .collect()
@@ -44,13 +47,36 @@ fn overlaps(map: &HashMap<(i32, i32), i32>) -> usize {
     map.values().filter(|&n| *n > 1).count()
 }
 
+pub struct Day05;
+
+impl Solution for Day05 {
+    const DAY: u32 = 5;
+
+    type Input = Vec<Line>;
+    type Answer1 = usize;
+    type Answer2 = usize;
+
+    fn parse(input: &str) -> Result<Vec<Line>, ParseError> {
+        parse_all(Self::DAY, input, parse::lines)
+    }
+
+    fn part1(lines: &Vec<Line>) -> usize {
+        overlaps(&record_lines(&without_diagonals(lines)))
+    }
+
+    fn part2(lines: &Vec<Line>) -> usize {
+        overlaps(&record_lines(lines))
+    }
+}
+
 mod parse {
     use nom::bytes::complete::tag;
     use nom::character::complete::{i32 as num, newline};
     use nom::IResult;
-    use nom::multi::separated_list1;
     use nom::sequence::separated_pair;
 
+    use crate::util::lines1;
+
     use super::*;
 
     fn coord(input: &str) -> IResult<&str, (i32, i32)> {
@@ -62,13 +88,14 @@ mod parse {
     }
 
     pub(crate) fn lines(input: &str) -> IResult<&str, Vec<Line>> {
-        separated_list1(newline, line)(input)
+        lines1(newline, line)(input)
     }
 }
 
 #[cfg(test)]
 mod test {
-    use crate::util::puzzle_input;
+    use crate::Part;
+    use crate::verify::assert_answer;
 
     use super::*;
 
@@ -81,6 +108,13 @@ mod test {
         assert_eq!(*lines.first().unwrap(), ((0, 9), (5, 9)));
     }
 
+    #[test]
+    fn test_parse_malformed() {
+        let err = Day05::parse("0,9 -> 5,9\n8,0 -> x,8\n9,4 -> 3,4").unwrap_err();
+        assert_eq!((err.day, err.line, err.column), (5, 2, 8));
+        assert_eq!((err.expected.as_str(), err.snippet.as_str()), ("a number", "x,8"));
+    }
+
     #[test]
     fn test_overlapping_cardinals() {
         let (_, lines) = parse::lines(TEST_INPUT).unwrap();
@@ -92,12 +126,7 @@ mod test {
 
     #[test]
     fn part1() {
-        let input = puzzle_input("05");
-        let (_, lines) = parse::lines(&input).unwrap();
-        let cardinals: Vec<Line> = without_diagonals(&lines);
-        let map = record_lines(&cardinals);
-
-        assert_eq!(overlaps(&map), 3990);
+        assert_answer(&Day05, Part::One);
     }
 
     #[test]
@@ -110,10 +139,6 @@ mod test {
 
     #[test]
     fn part2() {
-        let input = puzzle_input("05");
-        let (_, lines) = parse::lines(&input).unwrap();
-        let map = record_lines(&lines);
-
-        assert_eq!(overlaps(&map), 21305);
+        assert_answer(&Day05, Part::Two);
     }
 }
\ No newline at end of file
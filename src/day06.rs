@@ -1,3 +1,6 @@
+use crate::Solution;
+use crate::util::{parse_all, ParseError};
+
 fn fish_freqs(fish_list: Vec<u64>) -> Vec<u64> {
     let mut freqs: Vec<u64> = [0].repeat(9);
     for fish in fish_list {
@@ -6,11 +9,41 @@ fn fish_freqs(fish_list: Vec<u64>) -> Vec<u64> {
     freqs
 }
 
-fn grow(freqs: &mut Vec<u64>) {
+fn grow(freqs: &mut [u64]) {
     freqs.rotate_left(1);
     freqs[6] += freqs[8];
 }
 
+fn population_after(fish: Vec<u64>, days: u32) -> u64 {
+    let mut freqs = fish_freqs(fish);
+    for _ in 0..days {
+        grow(&mut freqs);
+    }
+    freqs.iter().sum()
+}
+
+pub struct Day06;
+
+impl Solution for Day06 {
+    const DAY: u32 = 6;
+
+    type Input = Vec<u64>;
+    type Answer1 = u64;
+    type Answer2 = u64;
+
+    fn parse(input: &str) -> Result<Vec<u64>, ParseError> {
+        parse_all(Self::DAY, input, parse::lanternfish_list)
+    }
+
+    fn part1(fish: &Vec<u64>) -> u64 {
+        population_after(fish.clone(), 80)
+    }
+
+    fn part2(fish: &Vec<u64>) -> u64 {
+        population_after(fish.clone(), 256)
+    }
+}
+
 mod parse {
     use nom::bytes::complete::tag;
     use nom::character::complete::u64 as num;
@@ -22,8 +55,10 @@ mod parse {
     }
 }
 
+#[cfg(test)]
 mod test {
-    use crate::util::puzzle_input;
+    use crate::Part;
+    use crate::verify::assert_answer;
 
     use super::*;
 
@@ -57,23 +92,11 @@ mod test {
 
     #[test]
     fn part1() {
-        let input = puzzle_input("06");
-        let (_, fish) = parse::lanternfish_list(&input).unwrap();
-        let mut freqs = fish_freqs(fish);
-        for _ in 0..80 {
-            grow(&mut freqs);
-        }
-        assert_eq!(freqs.iter().sum::<u64>(), 387413);
+        assert_answer(&Day06, Part::One);
     }
 
     #[test]
     fn part2() {
-        let input = puzzle_input("06");
-        let (_, fish) = parse::lanternfish_list(&input).unwrap();
-        let mut freqs = fish_freqs(fish);
-        for _ in 0..256 {
-            grow(&mut freqs);
-        }
-        assert_eq!(freqs.iter().sum::<u64>(), 1738377086345);
+        assert_answer(&Day06, Part::Two);
     }
 }
\ No newline at end of file
@@ -1,11 +1,16 @@
-fn distances_linear(crabs: &Vec<i64>) -> Vec<i64> {
+use crate::Solution;
+use crate::util::{parse_all, ParseError};
+
+/// The total distance the crabs need to move to line up at each position from 0 to the furthest crab.
+fn distances_linear(crabs: &[i64]) -> Vec<i64> {
     let max = *crabs.iter().max().unwrap() as usize;
-    let mut dists = Vec::with_capacity(max);
+    let mut dists = Vec::with_capacity(max + 1);
     let mut current_dist: i64 = crabs.iter().sum();
+    dists.push(current_dist);
     let mut i: usize = 0;
 
     for pos in 0..max {
-        while i < crabs.len() && &crabs[i] <= &(pos as i64) { i += 1; }
+        while i < crabs.len() && crabs[i] <= pos as i64 { i += 1; }
 
         current_dist += 2 * i as i64 - crabs.len() as i64;
         dists.push(current_dist);
@@ -13,11 +18,11 @@ fn distances_linear(crabs: &Vec<i64>) -> Vec<i64> {
     dists
 }
 
-fn distances_nonlinear(crabs: &Vec<i64>) -> Vec<i64> {
+fn distances_nonlinear(crabs: &[i64]) -> Vec<i64> {
     let max = *crabs.iter().max().unwrap() as usize;
-    let mut dists = Vec::with_capacity(max);
+    let mut dists = Vec::with_capacity(max + 1);
 
-    for pos in 0..max {
+    for pos in 0..=max {
         let mut total = 0;
         for c in crabs {
             let n = (pos as i64 - c).abs();
@@ -29,20 +34,47 @@ fn distances_nonlinear(crabs: &Vec<i64>) -> Vec<i64> {
     dists
 }
 
+pub struct Day07;
+
+impl Solution for Day07 {
+    const DAY: u32 = 7;
+
+    type Input = Vec<i64>;
+    type Answer1 = i64;
+    type Answer2 = i64;
+
+    fn parse(input: &str) -> Result<Vec<i64>, ParseError> {
+        let mut crabs = parse_all(Self::DAY, input, parse::crab_list)?;
+        crabs.sort();
+        Ok(crabs)
+    }
+
+    fn part1(crabs: &Vec<i64>) -> i64 {
+        *distances_linear(crabs).iter().min().unwrap()
+    }
+
+    fn part2(crabs: &Vec<i64>) -> i64 {
+        *distances_nonlinear(crabs).iter().min().unwrap()
+    }
+}
 
 mod parse {
     use nom::bytes::complete::tag;
     use nom::character::complete::i64 as num;
+    use nom::combinator::verify;
     use nom::IResult;
     use nom::multi::separated_list1;
 
+    /// Crabs' positions count from 0.
     pub(crate) fn crab_list(input: &str) -> IResult<&str, Vec<i64>> {
-        separated_list1(tag(","), num)(input)
+        separated_list1(tag(","), verify(num, |&pos| pos >= 0))(input)
     }
 }
 
+#[cfg(test)]
 mod test {
-    use crate::util::puzzle_input;
+    use crate::Part;
+    use crate::verify::assert_answer;
 
     use super::*;
 
@@ -62,13 +94,20 @@ mod test {
         assert_eq!(d, 37);
     }
 
+    #[test]
+    fn test_dists_cover_every_position() {
+        assert_eq!(distances_linear(&[0, 2]), [2, 2, 2]);
+        assert_eq!(distances_nonlinear(&[0, 2]), [3, 2, 3]);
+        let crabs = Day07::parse("0,0,0").unwrap();
+        assert_eq!((Day07::part1(&crabs), Day07::part2(&crabs)), (0, 0));
+        let crabs = Day07::parse("3,3").unwrap();
+        assert_eq!((Day07::part1(&crabs), Day07::part2(&crabs)), (0, 0));
+        assert!(Day07::parse("1,-2").is_err());
+    }
+
     #[test]
     fn part1() {
-        let input = puzzle_input("07");
-        let (_, mut fish) = parse::crab_list(&input).unwrap();
-        fish.sort();
-        let d = *distances_linear(&fish).iter().min().unwrap();
-        assert_eq!(d, 333755);
+        assert_answer(&Day07, Part::One);
     }
 
     #[test]
@@ -81,10 +120,6 @@ mod test {
 
     #[test]
     fn part2() {
-        let input = puzzle_input("07");
-        let (_, mut fish) = parse::crab_list(&input).unwrap();
-        fish.sort();
-        let d = *distances_nonlinear(&fish).iter().min().unwrap();
-        assert_eq!(d, 94017638);
+        assert_answer(&Day07, Part::Two);
     }
 }
\ No newline at end of file
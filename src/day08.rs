@@ -1,5 +1,8 @@
 use std::collections::HashMap;
 
+use crate::Solution;
+use crate::util::{parse_all, ParseError};
+
 pub struct Entry {
     patterns: Vec<String>,
     outputs: Vec<String>,
@@ -7,13 +10,7 @@ pub struct Entry {
 
 impl Entry {
     fn count_unique_lengths(&self) -> usize {
-        self.outputs.iter().filter(|p| match p.len() {
-            2 => true,
-            4 => true,
-            3 => true,
-            7 => true,
-            _ => false
-        }).count()
+        self.outputs.iter().filter(|p| matches!(p.len(), 2 | 4 | 3 | 7)).count()
     }
     fn deduce(&self) -> HashMap<String, &str> {
         let one = self.patterns.iter().find(|p| p.len() == 2).unwrap();
@@ -30,7 +27,7 @@ impl Entry {
 
         let upright = eight.chars().find(|p| !six.contains(*p)).unwrap();
         let three = self.patterns.iter().filter(|&p| p.len() == 5)
-            .find(|&p| (p.contains(one_first) && p.contains(one_second))).unwrap();
+            .find(|&p| p.contains(one_first) && p.contains(one_second)).unwrap();
 
         let two = self.patterns.iter().filter(|&p| p.len() == 5)
             .find(|&p| { p != three && p.contains(upright) }).unwrap();
@@ -63,6 +60,28 @@ impl Entry {
     }
 }
 
+pub struct Day08;
+
+impl Solution for Day08 {
+    const DAY: u32 = 8;
+
+    type Input = Vec<Entry>;
+    type Answer1 = usize;
+    type Answer2 = u32;
+
+    fn parse(input: &str) -> Result<Vec<Entry>, ParseError> {
+        parse_all(Self::DAY, input, parse::all_entries)
+    }
+
+    fn part1(entries: &Vec<Entry>) -> usize {
+        entries.iter().map(|e| e.count_unique_lengths()).sum()
+    }
+
+    fn part2(entries: &Vec<Entry>) -> u32 {
+        entries.iter().map(|e| e.decode(&e.deduce())).sum()
+    }
+}
+
 mod parse {
     use nom::bytes::complete::tag;
     use nom::character::complete::{alpha1, newline};
@@ -72,6 +91,7 @@ mod parse {
     use nom::sequence::separated_pair;
 
     use crate::day08::Entry;
+    use crate::util::lines1;
 
     fn pattern(input: &str) -> IResult<&str, String> {
         map(recognize(alpha1), |str: &str| {
@@ -93,12 +113,14 @@ mod parse {
     }
 
     pub(crate) fn all_entries(input: &str) -> IResult<&str, Vec<Entry>> {
-        separated_list1(newline, entry)(input)
+        lines1(newline, entry)(input)
     }
 }
 
+#[cfg(test)]
 mod test {
-    use crate::util::puzzle_input;
+    use crate::Part;
+    use crate::verify::assert_answer;
 
     use super::*;
 
@@ -129,11 +151,7 @@ gcafb gcf dcaebfg ecagb gf abcdeg gaef cafbge fdbac fegbdc | fgae cfgab fg bagce
 
     #[test]
     fn part1() {
-        let input = puzzle_input("08");
-        let (_, entries) = parse::all_entries(&input).unwrap();
-
-        let uniques: usize = entries.iter().map(|e| e.count_unique_lengths()).sum();
-        assert_eq!(uniques, 392);
+        assert_answer(&Day08, Part::One);
     }
 
     #[test]
@@ -148,12 +166,6 @@ gcafb gcf dcaebfg ecagb gf abcdeg gaef cafbge fdbac fegbdc | fgae cfgab fg bagce
 
     #[test]
     fn part2() {
-        let input = puzzle_input("08");
-        let (_, entries) = parse::all_entries(&input).unwrap();
-        let total: u32 = entries.iter().map(|e| {
-            let key = e.deduce();
-            e.decode(&key)
-        }).sum();
-        assert_eq!(total, 1004688)
+        assert_answer(&Day08, Part::Two);
     }
 }
\ No newline at end of file
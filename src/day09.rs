@@ -1,94 +1,131 @@
-use std::collections::{HashMap, HashSet};
-
-fn low_points(lava_map: &HashMap<(i32, i32), u32>, (cols, rows): (usize, usize)) -> Vec<(i32, i32)> {
-    let mut lows = Vec::new();
-    for y in 0..rows as i32 {
-        for x in 0..cols as i32 {
-            let current = *lava_map.get(&(x, y)).unwrap();
-            let above = *lava_map.get(&(x, y - 1)).unwrap_or(&9);
-            if current >= above { continue; }
-            let left = *lava_map.get(&(x - 1, y)).unwrap_or(&9);
-            if current >= left { continue; }
-            let right = *lava_map.get(&(x + 1, y)).unwrap_or(&9);
-            if current >= right { continue; }
-            let below = *lava_map.get(&(x, y + 1)).unwrap_or(&9);
-            if current >= below { continue; }
-            lows.push((x, y));
-        }
-    }
-    lows
+use std::collections::HashSet;
+
+use crate::Solution;
+use crate::util::{ansi, Grid, parse_all, ParseError};
+use crate::util::grid::Pos;
+
+fn low_points(heights: &Grid<u8>) -> Vec<Pos> {
+    heights.positions().filter(|&pos| {
+        let current = heights[pos];
+        heights.neighbours4(pos).all(|n| current < heights[n])
+    }).collect()
 }
 
-fn risk_total(lava_map: &HashMap<(i32, i32), u32>, dims: (usize, usize)) -> u32 {
-    let lowest = low_points(&lava_map, dims);
-    lowest.iter().map(|pos| {
-        lava_map.get(pos).unwrap() + 1
+fn risk_total(heights: &Grid<u8>) -> u32 {
+    let lowest = low_points(heights);
+    lowest.iter().map(|&pos| {
+        heights[pos] as u32 + 1
     }).sum()
 }
 
-fn reachable_from(lava_map: &HashMap<(i32, i32), u32>, start: (i32, i32)) -> u32 {
+fn reachable_from(heights: &Grid<u8>, start: Pos) -> u32 {
     let mut stack = vec![start];
     let mut seen = HashSet::new();
     let mut total = 0;
 
     loop {
         if let Some(current) = stack.pop() {
-            let (x, y) = current;
             if seen.contains(&current) { continue; }
             seen.insert(current);
 
-            let h = *lava_map.get(&current).unwrap_or(&9);
-            if h >= 9 { continue; }
+            if heights[current] >= 9 { continue; }
 
             total += 1;
-            stack.push((x + 1, y));
-            stack.push((x - 1, y));
-            stack.push((x, y - 1));
-            stack.push((x, y + 1));
+            stack.extend(heights.neighbours4(current));
         } else {
             break total;
         }
     }
 }
 
-fn top3_basins(lava_map: &HashMap<(i32, i32), u32>, dims: (usize, usize)) -> u32 {
-    let lowest = low_points(&lava_map, dims);
+fn top3_basins(heights: &Grid<u8>) -> u32 {
+    let lowest = low_points(heights);
     let mut basins: Vec<u32> = lowest.iter().map(|&pos|
-        reachable_from(&lava_map, pos)
+        reachable_from(heights, pos)
     ).collect();
     basins.sort_by_key(|&n| std::cmp::Reverse(n));
     basins.iter().take(3).product()
 }
 
-mod parse {
-    use nom::character::complete::{newline, one_of};
-    use nom::combinator::map;
-    use nom::IResult;
-    use nom::multi::{many1, separated_list1};
+/// Labels each cell with the low point it drains to, as an index into `low_points`. 9s belong to no basin.
+fn basins(heights: &Grid<u8>) -> Grid<Option<usize>> {
+    let mut labels = heights.map(|_| None);
+    for (basin, low) in low_points(heights).into_iter().enumerate() {
+        let mut stack = vec![low];
+        while let Some(pos) = stack.pop() {
+            if heights[pos] >= 9 || labels[pos].is_some() { continue; }
+            labels[pos] = Some(basin);
+            stack.extend(heights.neighbours4(pos));
+        }
+    }
+    labels
+}
 
-    use super::*;
+const BASIN_COLOURS: [u8; 12] = [24, 28, 88, 90, 94, 30, 54, 58, 130, 23, 52, 17];
+
+/// The heightmap with each basin on its own background colour and its low point in bold.
+pub fn render_basins(heights: &Grid<u8>) -> String {
+    let labels = basins(heights);
+    let lows: HashSet<Pos> = low_points(heights).into_iter().collect();
+
+    let mut out = String::new();
+    for pos in heights.positions() {
+        let style = match labels[pos] {
+            None => ansi::DIM.to_owned(),
+            Some(basin) => {
+                let colour = ansi::bg(BASIN_COLOURS[basin % BASIN_COLOURS.len()]);
+                if lows.contains(&pos) { format!("{}{}", ansi::BOLD, colour) } else { colour }
+            }
+        };
+        out.push_str(&ansi::paint(heights[pos], &style));
+        if pos.0 + 1 == heights.width() {
+            out.push('\n');
+        }
+    }
+    out
+}
+
+pub struct Day09;
+
+impl Solution for Day09 {
+    const DAY: u32 = 9;
+
+    type Input = Grid<u8>;
+    type Answer1 = u32;
+    type Answer2 = u32;
+
+    fn parse(input: &str) -> Result<Grid<u8>, ParseError> {
+        parse_all(Self::DAY, input, parse::lava_map)
+    }
+
+    fn part1(heights: &Grid<u8>) -> u32 {
+        risk_total(heights)
+    }
 
-    fn row(input: &str) -> IResult<&str, Vec<u32>> {
-        many1(map(one_of("0123456789"), |c: char| c.to_digit(10).unwrap()))(input)
+    fn part2(heights: &Grid<u8>) -> u32 {
+        top3_basins(heights)
     }
 
-    pub(crate) fn lava_map(input: &str) -> IResult<&str, ((usize, usize), HashMap<(i32, i32), u32>)> {
-        map(separated_list1(newline, row),
-            |vecs| {
-                let (cols, rows) = (vecs[0].len(), vecs.len());
-                let mut map = HashMap::with_capacity(rows * cols);
-                for (y, row) in vecs.iter().enumerate() {
-                    for (x, item) in row.iter().enumerate() {
-                        map.insert((x as i32, y as i32), *item);
-                    }
-                }
-                ((cols, rows), map)
-            })(input)
+    fn frames(heights: &Grid<u8>, _limit: usize) -> Vec<String> {
+        vec![render_basins(heights)]
     }
 }
 
+mod parse {
+    use nom::IResult;
+
+    use crate::util::Grid;
+    use crate::util::grid::parse::digits;
+
+    pub(crate) fn lava_map(input: &str) -> IResult<&str, Grid<u8>> {
+        digits(input)
+    }
+}
+
+#[cfg(test)]
 mod test {
-    use crate::util::puzzle_input;
+    use crate::Part;
+    use crate::verify::assert_answer;
 
     use super::*;
 
@@ -96,45 +133,63 @@ mod test {
 
     #[test]
     fn test_parse() {
-        let (_, ((_, _), lava_map)) = parse::lava_map(TEST_INPUT).unwrap();
-        assert_eq!(*lava_map.get(&(0, 0)).unwrap(), 2);
-        assert_eq!(*lava_map.get(&(0, 4)).unwrap(), 9);
-        assert_eq!(*lava_map.get(&(9, 0)).unwrap(), 0);
-        assert_eq!(*lava_map.get(&(9, 4)).unwrap(), 8);
+        let (_, heights) = parse::lava_map(TEST_INPUT).unwrap();
+        assert_eq!((heights.width(), heights.height()), (10, 5));
+        assert_eq!(heights[(0, 0)], 2);
+        assert_eq!(heights[(0, 4)], 9);
+        assert_eq!(heights[(9, 0)], 0);
+        assert_eq!(heights[(9, 4)], 8);
     }
 
     #[test]
     fn test_risk_total() {
-        let (_, (dims, lava_map)) = parse::lava_map(TEST_INPUT).unwrap();
-        assert_eq!(risk_total(&lava_map, dims), 15)
+        let (_, heights) = parse::lava_map(TEST_INPUT).unwrap();
+        assert_eq!(risk_total(&heights), 15)
     }
 
     #[test]
     fn part1() {
-        let input = puzzle_input("09");
-        let (_, (dims, lava_map)) = parse::lava_map(&input).unwrap();
-        assert_eq!(risk_total(&lava_map, dims), 566)
+        assert_answer(&Day09, Part::One);
     }
 
     #[test]
     fn test_reachable_from() {
-        let (_, (_, lava_map)) = parse::lava_map(TEST_INPUT).unwrap();
-        assert_eq!(reachable_from(&lava_map, (1, 0)), 3);
-        assert_eq!(reachable_from(&lava_map, (9, 0)), 9);
-        assert_eq!(reachable_from(&lava_map, (2, 2)), 14);
-        assert_eq!(reachable_from(&lava_map, (6, 4)), 9);
+        let (_, heights) = parse::lava_map(TEST_INPUT).unwrap();
+        assert_eq!(reachable_from(&heights, (1, 0)), 3);
+        assert_eq!(reachable_from(&heights, (9, 0)), 9);
+        assert_eq!(reachable_from(&heights, (2, 2)), 14);
+        assert_eq!(reachable_from(&heights, (6, 4)), 9);
     }
 
     #[test]
     fn test_top3_basins() {
-        let (_, (dims, lava_map)) = parse::lava_map(TEST_INPUT).unwrap();
-        assert_eq!(top3_basins(&lava_map, dims), 1134)
+        let (_, heights) = parse::lava_map(TEST_INPUT).unwrap();
+        assert_eq!(top3_basins(&heights), 1134)
+    }
+
+    #[test]
+    fn test_basins() {
+        let (_, heights) = parse::lava_map(TEST_INPUT).unwrap();
+        let labels = basins(&heights);
+        assert_eq!(labels[(0, 0)], labels[(1, 0)]);
+        assert_eq!(labels[(2, 0)], None);
+        assert_ne!(labels[(0, 0)], labels[(9, 0)]);
+        assert_eq!(labels.cells().iter().filter(|&&l| l == labels[(2, 2)]).count(), 14);
+    }
+
+    #[test]
+    fn test_render_basins() {
+        let (_, heights) = parse::lava_map(TEST_INPUT).unwrap();
+        let rendered = render_basins(&heights);
+        assert_eq!(ansi::strip(&rendered), heights.to_string());
+        for &colour in &BASIN_COLOURS[..4] {
+            assert!(rendered.contains(&ansi::bg(colour)));
+        }
+        assert!(!rendered.contains(&ansi::bg(BASIN_COLOURS[4])));
     }
 
     #[test]
     fn part2() {
-        let input = puzzle_input("09");
-        let (_, (dims, lava_map)) = parse::lava_map(&input).unwrap();
-        assert_eq!(top3_basins(&lava_map, dims), 891684)
+        assert_answer(&Day09, Part::Two);
     }
 }
\ No newline at end of file
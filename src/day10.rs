@@ -1,3 +1,6 @@
+use crate::Solution;
+use crate::util::{parse_all, ParseError};
+
 #[derive(Clone, Copy, Debug, Eq, PartialEq)]
 pub enum ChunkPart {
     ParenOpen,
@@ -32,7 +35,7 @@ impl ChunkPart {
     }
 }
 
-fn fix_error(line: &Vec<ChunkPart>) -> ChunkError {
+fn fix_error(line: &[ChunkPart]) -> ChunkError {
     let mut stack = Vec::new();
 
     for &part in line {
@@ -58,7 +61,7 @@ fn fix_error(line: &Vec<ChunkPart>) -> ChunkError {
     ChunkError::Incomplete(stack.iter().map(|part| part.close()).collect())
 }
 
-fn score_corrupt(subsystem: &Vec<Vec<ChunkPart>>) -> u64 {
+fn score_corrupt(subsystem: &[Vec<ChunkPart>]) -> u64 {
     subsystem.iter().filter_map(|line| {
         match fix_error(line) {
             ChunkError::Incomplete(_) => None,
@@ -71,7 +74,7 @@ fn score_corrupt(subsystem: &Vec<Vec<ChunkPart>>) -> u64 {
     }).sum()
 }
 
-fn score_incomplete(subsystem: &Vec<Vec<ChunkPart>>) -> u64 {
+fn score_incomplete(subsystem: &[Vec<ChunkPart>]) -> u64 {
     let mut scores: Vec<u64> = subsystem.iter().filter_map(|line| {
         match fix_error(line) {
             ChunkError::Incomplete(closers) => {
@@ -96,11 +99,35 @@ fn score_incomplete(subsystem: &Vec<Vec<ChunkPart>>) -> u64 {
     scores[scores.len() / 2]
 }
 
+pub struct Day10;
+
+impl Solution for Day10 {
+    const DAY: u32 = 10;
+
+    type Input = Vec<Vec<ChunkPart>>;
+    type Answer1 = u64;
+    type Answer2 = u64;
+
+    fn parse(input: &str) -> Result<Vec<Vec<ChunkPart>>, ParseError> {
+        parse_all(Self::DAY, input, parse::subsystem)
+    }
+
+    fn part1(subsystem: &Vec<Vec<ChunkPart>>) -> u64 {
+        score_corrupt(subsystem)
+    }
+
+    fn part2(subsystem: &Vec<Vec<ChunkPart>>) -> u64 {
+        score_incomplete(subsystem)
+    }
+}
+
 mod parse {
     use nom::character::complete::{newline, one_of};
     use nom::combinator::map;
     use nom::IResult;
-    use nom::multi::{many1, separated_list1};
+    use nom::multi::many1;
+
+    use crate::util::lines1;
 
     use super::*;
 
@@ -125,12 +152,14 @@ mod parse {
     }
 
     pub(crate) fn subsystem(input: &str) -> IResult<&str, Vec<Vec<ChunkPart>>> {
-        separated_list1(newline, line)(input)
+        lines1(newline, line)(input)
     }
 }
 
+#[cfg(test)]
 mod test {
-    use crate::util::puzzle_input;
+    use crate::Part;
+    use crate::verify::assert_answer;
 
     use super::*;
 
@@ -163,9 +192,7 @@ mod test {
 
     #[test]
     fn part1() {
-        let input = puzzle_input("10");
-        let (_, subsystem) = parse::subsystem(&input).unwrap();
-        assert_eq!(score_corrupt(&subsystem), 265527);
+        assert_answer(&Day10, Part::One);
     }
 
     #[test]
@@ -176,8 +203,6 @@ mod test {
 
     #[test]
     fn part2() {
-        let input = puzzle_input("10");
-        let (_, subsystem) = parse::subsystem(&input).unwrap();
-        assert_eq!(score_incomplete(&subsystem), 3969823589);
+        assert_answer(&Day10, Part::Two);
     }
 }
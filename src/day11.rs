@@ -1,46 +1,62 @@
+use std::collections::HashMap;
+use std::fmt::{Display, Formatter};
+
+use crate::Solution;
+use crate::util::{self, ansi, BitSet, parse_all, ParseError};
+use crate::util::grid::Pos;
+
+/// A rectangular colony of octopuses, of any size.
+#[derive(Clone)]
 pub struct Grid {
-    cells: Vec<u8>,
-    flash_queue: Vec<usize>,
-    seen: u128,
+    octopuses: util::Grid<u8>,
+    flash_queue: Vec<Pos>,
+    seen: BitSet,
 }
 
 impl Grid {
-    fn new(cells: Vec<u8>) -> Grid {
+    fn new(octopuses: util::Grid<u8>) -> Grid {
+        let seen = BitSet::new(octopuses.len());
         Grid {
-            cells,
+            octopuses,
             flash_queue: Vec::new(),
-            seen: 0,
+            seen,
         }
     }
 
-    fn incr(&mut self, i: i32) {
-        if i < 0 || i >= self.cells.len() as i32 { return; }
+    pub fn width(&self) -> usize {
+        self.octopuses.width()
+    }
+
+    pub fn height(&self) -> usize {
+        self.octopuses.height()
+    }
 
-        let mask: u128 = 1 << (i as u128);
-        if self.seen & mask != 0 { return; }
+    fn incr(&mut self, pos: Pos) {
+        let i = self.octopuses.index(pos);
+        if self.seen.contains(i) { return; }
 
-        let u = i as usize;
-        self.cells[u] += 1;
-        if self.cells[u] > 9 {
-            self.seen |= mask;
-            self.cells[u] = 0;
+        let energy = &mut self.octopuses[pos];
+        *energy += 1;
+        if *energy > 9 {
+            self.seen.insert(i);
+            *energy = 0;
 
-            self.flash_queue.push(u);
+            self.flash_queue.push(pos);
         }
     }
 
     pub fn step(&mut self) -> usize {
-        for i in 0..100 {
-            self.incr(i)
+        self.seen.clear();
+        for pos in self.octopuses.positions() {
+            self.incr(pos)
         }
 
         let mut total = 0;
         loop {
             if let Some(next) = self.flash_queue.pop() {
                 total += 1;
-                self.flash(next as i32);
+                self.flash(next);
             } else {
-                self.seen = 0;
                 break total;
             }
         }
@@ -54,68 +70,158 @@ impl Grid {
         flashes
     }
 
-    pub fn steps_until_sync(&mut self) -> usize {
-        let mut steps = 0;
-        loop {
+    /// The octopuses that flashed during the most recent step, row by row.
+    pub fn flashed(&self) -> impl Iterator<Item=Pos> + '_ {
+        self.seen.iter().map(|i| self.octopuses.pos(i))
+    }
+
+    pub fn is_synced(&self) -> bool {
+        self.octopuses.cells().iter().all(|&c| c == 0)
+    }
+
+    /// Steps until every octopus flashes at once, the colony falls into a cycle that never syncs, or `limit` steps
+    /// have passed, recording which octopuses flashed on each step along the way.
+    pub fn simulate(&mut self, limit: Option<usize>) -> Simulation {
+        let mut history = Vec::new();
+        let mut seen_states = HashMap::from([(self.octopuses.clone(), 0)]);
+        let outcome = loop {
+            if limit == Some(history.len()) {
+                break SyncOutcome::LimitReached(history.len());
+            }
+
             self.step();
-            steps += 1;
-            if self.cells.iter().all(|&c| c == 0) {
-                break steps;
+            history.push(self.flashed().collect());
+            let step = history.len();
+            if self.is_synced() {
+                break SyncOutcome::Synced(step);
             }
-        }
+            if let Some(start) = seen_states.insert(self.octopuses.clone(), step) {
+                break SyncOutcome::Cycle { start, length: step - start };
+            }
+        };
+        Simulation { history, outcome }
+    }
+
+    pub fn steps_until_sync(&mut self) -> SyncOutcome {
+        self.simulate(None).outcome
     }
 
-    fn flash(&mut self, start: i32) {
-        let x = start % 10;
-        if x > 0 {
-            self.incr(start - 11);
-            self.incr(start - 1);
-            self.incr(start + 9);
+    /// The colony with this step's flashes lit up and every other octopus shaded by its energy level.
+    pub fn render_ansi(&self) -> String {
+        let flash = format!("{}{}", ansi::BOLD, ansi::fg(226));
+        let mut out = String::new();
+        for pos in self.octopuses.positions() {
+            let energy = self.octopuses[pos];
+            let style = if self.seen.contains(self.octopuses.index(pos)) {
+                flash.clone()
+            } else {
+                ansi::fg(236 + 2 * energy)
+            };
+            out.push_str(&ansi::paint(energy, &style));
+            if pos.0 + 1 == self.width() {
+                out.push('\n');
+            }
         }
-        self.incr(start - 10);
-        self.incr(start + 10);
-        if x < 9 {
-            self.incr(start - 9);
-            self.incr(start + 1);
-            self.incr(start + 11);
+        out
+    }
+
+    /// Renders the colony as it is now and after each step, `limit` frames in all, stopping early if it syncs.
+    pub fn frames(&mut self, limit: usize) -> Vec<String> {
+        util::frames(self, limit, |grid| {
+            let flashes = grid.step();
+            (flashes, grid.is_synced())
+        }, |grid, step, flashes| format!("step {}, {} flashes\n{}", step, flashes, grid.render_ansi()))
+    }
+
+    fn flash(&mut self, start: Pos) {
+        for pos in self.octopuses.neighbours8(start) {
+            self.incr(pos);
         }
     }
 }
 
-impl std::fmt::Display for Grid {
-    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
-        for y in 0..10 {
-            for x in 0..10 {
-                let c = self.cells[y * 10 + x];
-                write!(f, "{}", c)?;
-            }
-            writeln!(f)?;
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum SyncOutcome {
+    /// Every octopus flashed together on this step.
+    Synced(usize),
+    /// The state after step `start` comes round again every `length` steps without ever syncing.
+    Cycle { start: usize, length: usize },
+    /// Gave up after this many steps without syncing or repeating.
+    LimitReached(usize),
+}
+
+impl Display for SyncOutcome {
+    fn fmt(&self, f: &mut Formatter<'_>) -> std::fmt::Result {
+        match self {
+            SyncOutcome::Synced(step) => write!(f, "{}", step),
+            SyncOutcome::Cycle { start, length } =>
+                write!(f, "never syncs, enters a cycle of length {} at step {}", length, start),
+            SyncOutcome::LimitReached(steps) => write!(f, "no sync within {} steps", steps),
         }
-        Result::Ok(())
+    }
+}
+
+pub struct Simulation {
+    /// The octopuses that flashed on each step; `history[0]` is step 1.
+    pub history: Vec<Vec<Pos>>,
+    pub outcome: SyncOutcome,
+}
+
+impl Simulation {
+    pub fn total_flashes(&self) -> usize {
+        self.history.iter().map(Vec::len).sum()
+    }
+}
+
+pub struct Day11;
+
+impl Solution for Day11 {
+    const DAY: u32 = 11;
+
+    type Input = Grid;
+    type Answer1 = usize;
+    type Answer2 = SyncOutcome;
+
+    fn parse(input: &str) -> Result<Grid, ParseError> {
+        parse_all(Self::DAY, input, parse::grid)
+    }
+
+    fn part1(grid: &Grid) -> usize {
+        grid.clone().steps(100)
+    }
+
+    fn part2(grid: &Grid) -> SyncOutcome {
+        grid.clone().steps_until_sync()
+    }
+
+    fn frames(grid: &Grid, limit: usize) -> Vec<String> {
+        grid.clone().frames(limit)
+    }
+}
+
+impl Display for Grid {
+    fn fmt(&self, f: &mut Formatter<'_>) -> std::fmt::Result {
+        write!(f, "{}", self.octopuses)
     }
 }
 
 mod parse {
-    use nom::character::complete::{newline, one_of};
-    use nom::combinator::{map, opt};
+    use nom::combinator::map;
     use nom::IResult;
-    use nom::multi::fold_many1;
-    use nom::sequence::terminated;
+
+    use crate::util::grid::parse::digits;
 
     use super::*;
 
     pub(crate) fn grid(input: &str) -> IResult<&str, Grid> {
-        let digit = terminated(one_of("0123456789"), opt(newline));
-        let digit_as_u8 = map(digit, |c: char| c.to_digit(10).unwrap() as u8);
-        map(fold_many1(digit_as_u8, || Vec::with_capacity(100), |mut acc: Vec<u8>, d| {
-            acc.push(d);
-            acc
-        }), |v| Grid::new(v))(input)
+        map(digits, Grid::new)(input)
     }
 }
 
+#[cfg(test)]
 mod test {
-    use crate::util::puzzle_input;
+    use crate::Part;
+    use crate::verify::assert_answer;
 
     use super::*;
 
@@ -134,8 +240,53 @@ mod test {
     fn test_parse() {
         let (_, grid) = parse::grid(TEST_INPUT).unwrap();
 
-        assert_eq!(grid.cells.len(), 100);
-        assert_eq!(grid.cells[99], 6);
+        assert_eq!(grid.octopuses.len(), 100);
+        assert_eq!(grid.octopuses[(9, 9)], 6);
+    }
+
+    #[test]
+    fn test_small_grid() {
+        let (_, mut grid) = parse::grid("11111\n19991\n19191\n19991\n11111").unwrap();
+        assert_eq!((grid.width(), grid.height()), (5, 5));
+
+        assert_eq!(grid.step(), 9);
+        assert_eq!(grid.to_string(), "34543\n40004\n50005\n40004\n34543\n");
+        assert_eq!(grid.step(), 0);
+        assert_eq!(grid.to_string(), "45654\n51115\n61116\n51115\n45654\n");
+    }
+
+    #[test]
+    fn test_large_grid() {
+        let row = "9".repeat(40);
+        let input = vec![row.as_str(); 30].join("\n");
+        let (_, mut grid) = parse::grid(&input).unwrap();
+        assert_eq!((grid.width(), grid.height()), (40, 30));
+
+        assert_eq!(grid.step(), 1200);
+        assert_eq!(grid.steps_until_sync(), SyncOutcome::Synced(10));
+    }
+
+    #[test]
+    fn test_render_ansi() {
+        let (_, mut grid) = parse::grid("11111\n19991\n19191\n19991\n11111").unwrap();
+        grid.step();
+
+        let rendered = grid.render_ansi();
+        assert_eq!(ansi::strip(&rendered), grid.to_string());
+        let flash = format!("{}{}0", ansi::BOLD, ansi::fg(226));
+        assert_eq!(rendered.matches(&flash).count(), 9);
+    }
+
+    #[test]
+    fn test_frames() {
+        let (_, mut grid) = parse::grid(TEST_INPUT).unwrap();
+        let frames = grid.frames(3);
+        assert_eq!(frames.len(), 3);
+        assert!(frames[0].starts_with("step 0, 0 flashes\n"));
+        assert!(frames[2].starts_with("step 2, 35 flashes\n"));
+
+        let (_, mut grid) = parse::grid("0").unwrap();
+        assert_eq!(grid.frames(100).len(), 11);
     }
 
     #[test]
@@ -148,11 +299,7 @@ mod test {
 
     #[test]
     fn part1() {
-        let input = puzzle_input("11");
-        let (_, mut grid) = parse::grid(&input).unwrap();
-
-        let flashes = grid.steps(100);
-        assert_eq!(flashes, 1617);
+        assert_answer(&Day11, Part::One);
     }
 
     #[test]
@@ -160,15 +307,35 @@ mod test {
         let (_, mut grid) = parse::grid(TEST_INPUT).unwrap();
 
         let steps = grid.steps_until_sync();
-        assert_eq!(steps, 195);
+        assert_eq!(steps, SyncOutcome::Synced(195));
     }
 
     #[test]
-    fn part2() {
-        let input = puzzle_input("11");
-        let (_, mut grid) = parse::grid(&input).unwrap();
+    fn test_history() {
+        let (_, mut grid) = parse::grid(TEST_INPUT).unwrap();
+        let sim = grid.simulate(Some(10));
 
-        let steps = grid.steps_until_sync();
-        assert_eq!(steps, 258);
+        assert_eq!(sim.outcome, SyncOutcome::LimitReached(10));
+        assert_eq!(sim.history.len(), 10);
+        assert_eq!(sim.total_flashes(), 204);
+        assert!(sim.history[0].is_empty());
+        assert_eq!(sim.history[1].len(), 35);
+        assert!(sim.history[1].contains(&(2, 0)));
+        assert!(!sim.history[1].contains(&(0, 0)));
+    }
+
+    #[test]
+    fn test_never_syncs() {
+        let (_, mut grid) = parse::grid("00\n23").unwrap();
+        let sim = grid.simulate(Some(1000));
+
+        assert_eq!(sim.outcome, SyncOutcome::Cycle { start: 7, length: 8 });
+        assert_eq!(sim.history.len(), 15);
+        assert_eq!(sim.outcome.to_string(), "never syncs, enters a cycle of length 8 at step 7");
+    }
+
+    #[test]
+    fn part2() {
+        assert_answer(&Day11, Part::Two);
     }
 }
\ No newline at end of file
@@ -1,5 +1,8 @@
 use std::collections::HashSet;
 
+use crate::Solution;
+use crate::util::{parse_all, ParseError};
+
 pub struct CaveMap {
     connections: Vec<(usize, usize)>,
     names: Vec<String>,
@@ -31,20 +34,19 @@ impl CaveMap {
         results.len() as u32
     }
 
-    fn count_paths_from(&self, start_id: usize, so_far: &Vec<usize>, allow_single_dupe: bool, output: &mut HashSet<Vec<usize>>) {
+    fn count_paths_from(&self, start_id: usize, so_far: &[usize], allow_single_dupe: bool, output: &mut HashSet<Vec<usize>>) {
         let final_end = self.get_id("end").unwrap();
         let beginning = self.get_id("start").unwrap();
         if start_id == final_end {
-            output.insert(so_far.clone());
+            output.insert(so_far.to_vec());
         } else {
             let conns =
                 self.connections.iter().filter(|(start, end)| {
                     if *start != start_id { return false; }
                     if self.sizes[*end] == Size::Large { return true; }
-                    if !so_far.contains(&end) { return true; }
-                    return false;
+                    !so_far.contains(end)
                 });
-            let mut new_so_far = so_far.clone();
+            let mut new_so_far = so_far.to_vec();
             new_so_far.push(start_id);
 
             for &(_, end) in conns {
@@ -57,8 +59,7 @@ impl CaveMap {
                         if *start != start_id { return false; }
                         if *end == beginning { return false; }
                         if self.sizes[*end] == Size::Large { return false; }
-                        if so_far.contains(&end) { return true; }
-                        return false;
+                        so_far.contains(end)
                     });
 
                 for &(_, end) in revisits {
@@ -96,6 +97,7 @@ impl CaveMap {
         self.connections.push((end_id, start_id));
     }
 
+    #[cfg(test)]
     fn is_connected(&self, start: &str, end: &str) -> bool {
         let start_id = self.get_id(start).unwrap();
         let end_id = self.get_id(end).unwrap();
@@ -103,6 +105,28 @@ impl CaveMap {
     }
 }
 
+pub struct Day12;
+
+impl Solution for Day12 {
+    const DAY: u32 = 12;
+
+    type Input = CaveMap;
+    type Answer1 = u32;
+    type Answer2 = u32;
+
+    fn parse(input: &str) -> Result<CaveMap, ParseError> {
+        parse_all(Self::DAY, input, parse::cave_system)
+    }
+
+    fn part1(caves: &CaveMap) -> u32 {
+        caves.count_paths()
+    }
+
+    fn part2(caves: &CaveMap) -> u32 {
+        caves.count_paths_cheat()
+    }
+}
+
 #[derive(Hash, Eq, PartialEq)]
 pub enum Size {
     Small,
@@ -130,7 +154,7 @@ mod parse {
     pub(crate) fn cave_system(input: &str) -> IResult<&str, CaveMap> {
         fold_many1(
             terminated(connection, opt(newline)),
-            || CaveMap::new(),
+            CaveMap::new,
             |mut acc, (from, to)| {
                 acc.connect(from, to);
                 acc
@@ -139,8 +163,10 @@ mod parse {
     }
 }
 
+#[cfg(test)]
 mod test {
-    use crate::util::puzzle_input;
+    use crate::Part;
+    use crate::verify::assert_answer;
 
     use super::*;
 
@@ -210,9 +236,7 @@ start-RW";
 
     #[test]
     fn part1() {
-        let input = puzzle_input("12");
-        let (_, caves) = parse::cave_system(&input).unwrap();
-        assert_eq!(caves.count_paths(), 4378);
+        assert_answer(&Day12, Part::One);
     }
 
     #[test]
@@ -235,8 +259,6 @@ start-RW";
 
     #[test]
     fn part2() {
-        let input = puzzle_input("12");
-        let (_, caves) = parse::cave_system(&input).unwrap();
-        assert_eq!(caves.count_paths_cheat(), 133621);
+        assert_answer(&Day12, Part::Two);
     }
 }
\ No newline at end of file
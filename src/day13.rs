@@ -1,4 +1,6 @@
-use std::collections::HashSet;
+use crate::Solution;
+use crate::util::{Grid, parse_all, ParseError};
+use crate::util::grid::Pos;
 
 #[derive(Eq, PartialEq, Debug, Clone)]
 pub enum Direction {
@@ -13,45 +15,94 @@ pub struct Fold {
 }
 
 pub struct Instructions {
-    paper: HashSet<(u32, u32)>,
+    paper: Grid<bool>,
     folds: Vec<Fold>,
 }
 
-fn fold_paper(paper: HashSet<(u32, u32)>, f: &Fold) -> HashSet<(u32, u32)> {
-    let mut folded = HashSet::new();
-    for (x, y) in paper {
-        let d = match f.dir {
-            Direction::Up => y,
-            Direction::Left => x
-        };
+fn paper_with_dots(dots: &[Pos]) -> Grid<bool> {
+    let width = dots.iter().map(|&(x, _)| x + 1).max().unwrap_or(0);
+    let height = dots.iter().map(|&(_, y)| y + 1).max().unwrap_or(0);
+    let mut paper = Grid::filled(width, height, false);
+    for &pos in dots {
+        paper[pos] = true;
+    }
+    paper
+}
 
-        if d < f.coord {
-            folded.insert((x, y));
-        } else {
-            let new_d = 2 * f.coord - d;
-            match f.dir {
-                Direction::Up => folded.insert((x, new_d)),
-                Direction::Left => folded.insert((new_d, y))
-            };
-        }
+fn dots(paper: &Grid<bool>) -> usize {
+    paper.cells().iter().filter(|&&dot| dot).count()
+}
+
+fn fold_paper(paper: Grid<bool>, f: &Fold) -> Grid<bool> {
+    let coord = f.coord as usize;
+    let (width, height) = match f.dir {
+        Direction::Up => (paper.width(), coord),
+        Direction::Left => (coord, paper.height())
+    };
+
+    let mut folded = Grid::filled(width, height, false);
+    for (x, y) in paper.positions().filter(|&pos| paper[pos]) {
+        let pos = match f.dir {
+            Direction::Up if y > coord => (x, 2 * coord - y),
+            Direction::Left if x > coord => (2 * coord - x, y),
+            _ => (x, y)
+        };
+        folded.set(pos, true);
     }
     folded
 }
 
+fn render(paper: &Grid<bool>) -> String {
+    let width = paper.positions().filter(|&pos| paper[pos]).map(|(x, _)| x + 1).max().unwrap_or(0);
+    let height = paper.positions().filter(|&pos| paper[pos]).map(|(_, y)| y + 1).max().unwrap_or(0);
+
+    let mut out = String::with_capacity((width + 1) * height);
+    for row in paper.rows().take(height) {
+        out.extend(row[..width].iter().map(|&dot| if dot { '*' } else { ' ' }));
+        out.push('\n');
+    }
+    out
+}
+
+pub struct Day13;
+
+impl Solution for Day13 {
+    const DAY: u32 = 13;
+
+    type Input = Instructions;
+    type Answer1 = usize;
+    type Answer2 = String;
+
+    fn parse(input: &str) -> Result<Instructions, ParseError> {
+        parse_all(Self::DAY, input, parse::instructions)
+    }
+
+    fn part1(instr: &Instructions) -> usize {
+        dots(&fold_paper(instr.paper.clone(), &instr.folds[0]))
+    }
+
+    fn part2(instr: &Instructions) -> String {
+        render(&instr.folds.iter().fold(instr.paper.clone(), fold_paper))
+    }
+}
+
 mod parse {
     use nom::branch::alt;
     use nom::bytes::complete::tag;
     use nom::character::complete::{newline, u32 as num};
     use nom::combinator::{map, value};
     use nom::IResult;
-    use nom::multi::{fold_many1, separated_list1};
+    use nom::multi::many1;
     use nom::sequence::{preceded, separated_pair, terminated};
 
+    use crate::util::lines1;
+
     use super::*;
 
-    fn coord(input: &str) -> IResult<&str, (u32, u32)> {
-        terminated(separated_pair(num, tag(","), num),
-                   newline)(input)
+    fn coord(input: &str) -> IResult<&str, Pos> {
+        map(terminated(separated_pair(num, tag(","), num),
+                       newline),
+            |(x, y)| (x as usize, y as usize))(input)
     }
 
     fn fold(input: &str) -> IResult<&str, Fold> {
@@ -68,20 +119,17 @@ mod parse {
     }
 
     pub(crate) fn instructions(input: &str) -> IResult<&str, Instructions> {
-        let coords =
-            fold_many1(coord, HashSet::new,
-                       |mut acc, p| {
-                           acc.insert(p);
-                           acc
-                       });
-        let folds = separated_list1(newline, fold);
+        let coords = many1(coord);
+        let folds = lines1(newline, fold);
         map(separated_pair(coords, newline, folds),
-            |(c, f)| Instructions { paper: c, folds: f })(input)
+            |(c, f)| Instructions { paper: paper_with_dots(&c), folds: f })(input)
     }
 }
 
+#[cfg(test)]
 mod test {
-    use crate::util::puzzle_input;
+    use crate::Part;
+    use crate::verify::assert_answer;
 
     use super::*;
 
@@ -111,51 +159,24 @@ fold along x=5";
     fn test_parse() {
         let (_, instr) = parse::instructions(EXAMPLE_INPUT).unwrap();
         assert_eq!(instr.folds[1], Fold { coord: 5, dir: Direction::Left });
-        assert!(instr.paper.contains(&(9, 0)));
-        assert!(instr.paper.contains(&(0, 14)));
+        assert!(instr.paper[(9, 0)]);
+        assert!(instr.paper[(0, 14)]);
     }
 
     #[test]
     fn test_single_fold() {
         let (_, instr) = parse::instructions(EXAMPLE_INPUT).unwrap();
         let folded = fold_paper(instr.paper, &instr.folds[0]);
-        assert_eq!(folded.len(), 17);
+        assert_eq!(dots(&folded), 17);
     }
 
     #[test]
     fn part1() {
-        let input = puzzle_input("13");
-        let (_, instr) = parse::instructions(&input).unwrap();
-        let folded = fold_paper(instr.paper, &instr.folds[0]);
-        assert_eq!(folded.len(), 682);
+        assert_answer(&Day13, Part::One);
     }
 
     #[test]
     fn part2() {
-        use std::fmt::Write;
-
-        let input = puzzle_input("13");
-        let (_, instr) = parse::instructions(&input).unwrap();
-        let folded = instr.folds.iter().fold(instr.paper, fold_paper);
-
-        let mut actual = String::with_capacity(280);
-        for y in 0..6 {
-            for x in 0..39 {
-                let p = folded.contains(&(x, y));
-                if p { actual.write_char('*').unwrap(); } else { actual.write_char(' ').unwrap(); }
-            }
-            actual.write_char('\n').unwrap();
-        }
-
-        let expected = concat!(
-        "****  **   **  *  * ***  **** *  * ****\n",
-        "*    *  * *  * *  * *  *    * *  * *   \n",
-        "***  *  * *    *  * *  *   *  **** *** \n",
-        "*    **** * ** *  * ***   *   *  * *   \n",
-        "*    *  * *  * *  * * *  *    *  * *   \n",
-        "*    *  *  ***  **  *  * **** *  * ****\n"
-        );
-
-        assert_eq!(expected, actual);
+        assert_answer(&Day13, Part::Two);
     }
 }
\ No newline at end of file
@@ -1,6 +1,11 @@
 use std::collections::HashMap;
 
-fn steps(polymer: &str, count: u32, rules: &HashMap<(char, char), char>) -> HashMap<char, u64> {
+use crate::Solution;
+use crate::util::{parse_all, ParseError};
+
+type Rules = HashMap<(char, char), char>;
+
+fn steps(polymer: &str, count: u32, rules: &Rules) -> HashMap<char, u64> {
     let mut pairs = HashMap::new();
 
     for i in 0..polymer.len() - 1 {
@@ -23,13 +28,39 @@ fn steps(polymer: &str, count: u32, rules: &HashMap<(char, char), char>) -> Hash
     let mut freqs = HashMap::new();
 
     for ((ch, _), n) in pairs {
-        *freqs.entry(ch).or_insert(0 as u64) += n;
+        *freqs.entry(ch).or_insert(0_u64) += n;
     }
 
     *freqs.entry(polymer.chars().last().unwrap()).or_insert(0) += 1;
     freqs
 }
 
+fn spread(freqs: &HashMap<char, u64>) -> u64 {
+    freqs.values().max().unwrap() - freqs.values().min().unwrap()
+}
+
+pub struct Day14;
+
+impl Solution for Day14 {
+    const DAY: u32 = 14;
+
+    type Input = (String, Rules);
+    type Answer1 = u64;
+    type Answer2 = u64;
+
+    fn parse(input: &str) -> Result<(String, Rules), ParseError> {
+        parse_all(Self::DAY, input, parse::polymer)
+    }
+
+    fn part1((template, rules): &(String, Rules)) -> u64 {
+        spread(&steps(template, 10, rules))
+    }
+
+    fn part2((template, rules): &(String, Rules)) -> u64 {
+        spread(&steps(template, 40, rules))
+    }
+}
+
 mod parse {
     use std::collections::HashMap;
 
@@ -40,11 +71,13 @@ mod parse {
     use nom::multi::fold_many1;
     use nom::sequence::{pair, separated_pair, terminated};
 
+    use super::Rules;
+
     fn template(input: &str) -> IResult<&str, String> {
         map(terminated(alpha1, newline), String::from)(input)
     }
 
-    fn rules(input: &str) -> IResult<&str, HashMap<(char, char), char>> {
+    fn rules(input: &str) -> IResult<&str, Rules> {
         fold_many1(
             terminated(separated_pair(pair(anychar, anychar),
                                       tag(" -> "),
@@ -56,13 +89,15 @@ mod parse {
             })(input)
     }
 
-    pub(crate) fn polymer(input: &str) -> IResult<&str, (String, HashMap<(char, char), char>)> {
+    pub(crate) fn polymer(input: &str) -> IResult<&str, (String, Rules)> {
         separated_pair(template, newline, rules)(input)
     }
 }
 
+#[cfg(test)]
 mod test {
-    use crate::util::puzzle_input;
+    use crate::Part;
+    use crate::verify::assert_answer;
 
     use super::*;
 
@@ -102,14 +137,7 @@ CN -> C";
 
     #[test]
     fn part1() {
-        let input = puzzle_input("14");
-        let (_, (template, rules)) = parse::polymer(&input).unwrap();
-        let freqs = steps(template.as_str(), 10, &rules);
-
-        let least = *freqs.values().min().unwrap();
-        let most = *freqs.values().max().unwrap();
-
-        assert_eq!(most - least, 2068);
+        assert_answer(&Day14, Part::One);
     }
 
     #[test]
@@ -122,14 +150,7 @@ CN -> C";
 
     #[test]
     fn part2() {
-        let input = puzzle_input("14");
-        let (_, (template, rules)) = parse::polymer(&input).unwrap();
-        let freqs = steps(template.as_str(), 40, &rules);
-
-        let least = *freqs.values().min().unwrap();
-        let most = *freqs.values().max().unwrap();
-
-        assert_eq!(most - least, 2158894777814);
+        assert_answer(&Day14, Part::Two);
     }
 }
 
@@ -0,0 +1,172 @@
+use std::cmp::Reverse;
+use std::collections::BinaryHeap;
+
+use crate::Solution;
+use crate::util::{Grid, parse_all, ParseError};
+use crate::util::grid::Pos;
+
+/// The cheapest way from the top left to the bottom right corner.
+#[derive(Debug, PartialEq, Eq)]
+pub struct Path {
+    /// Total risk of every cell entered; the starting cell is never entered, so doesn't count.
+    pub risk: u32,
+    /// Every cell visited, from the top left corner to the bottom right one.
+    pub steps: Vec<Pos>,
+}
+
+/// The full map: the cave tiled `times` times in each direction, with each tile's risk levels one higher than the tile
+/// above or to its left, wrapping from 9 back round to 1.
+pub fn tile(risks: &Grid<u8>, times: usize) -> Grid<u8> {
+    let (width, height) = (risks.width(), risks.height());
+    let mut full = Grid::filled(width * times, height * times, 0);
+    for (x, y) in full.positions() {
+        let extra = (x / width + y / height) as u32;
+        let risk = risks[(x % width, y % height)] as u32 + extra;
+        full[(x, y)] = ((risk - 1) % 9 + 1) as u8;
+    }
+    full
+}
+
+/// Best-first search from corner to corner, where `heuristic` must never overestimate the remaining risk from a cell.
+fn search(risks: &Grid<u8>, heuristic: impl Fn(Pos) -> u32) -> Option<Path> {
+    let start = (0, 0);
+    let goal = (risks.width().checked_sub(1)?, risks.height().checked_sub(1)?);
+
+    let mut best = risks.map(|_| u32::MAX);
+    let mut came_from: Grid<Option<Pos>> = risks.map(|_| None);
+    let mut queue = BinaryHeap::from([Reverse((heuristic(start), 0, start))]);
+    best[start] = 0;
+
+    while let Some(Reverse((_, risk, pos))) = queue.pop() {
+        if pos == goal {
+            let mut steps = vec![goal];
+            while let Some(prev) = came_from[steps[steps.len() - 1]] {
+                steps.push(prev);
+            }
+            steps.reverse();
+            return Some(Path { risk, steps });
+        }
+        if risk > best[pos] { continue; }
+
+        for next in risks.neighbours4(pos) {
+            let next_risk = risk + risks[next] as u32;
+            if next_risk < best[next] {
+                best[next] = next_risk;
+                came_from[next] = Some(pos);
+                queue.push(Reverse((next_risk + heuristic(next), next_risk, next)));
+            }
+        }
+    }
+    None
+}
+
+pub fn dijkstra(risks: &Grid<u8>) -> Option<Path> {
+    search(risks, |_| 0)
+}
+
+/// Same result as [`dijkstra`], but steered towards the goal. Every cell costs at least 1 to enter, so the Manhattan
+/// distance to the goal is a lower bound on the remaining risk.
+pub fn a_star(risks: &Grid<u8>) -> Option<Path> {
+    let (gx, gy) = (risks.width().saturating_sub(1), risks.height().saturating_sub(1));
+    search(risks, |(x, y)| ((gx - x) + (gy - y)) as u32)
+}
+
+pub struct Day15;
+
+impl Solution for Day15 {
+    const DAY: u32 = 15;
+
+    type Input = Grid<u8>;
+    type Answer1 = u32;
+    type Answer2 = u32;
+
+    fn parse(input: &str) -> Result<Grid<u8>, ParseError> {
+        parse_all(Self::DAY, input, parse::risk_map)
+    }
+
+    fn part1(risks: &Grid<u8>) -> u32 {
+        dijkstra(risks).unwrap().risk
+    }
+
+    fn part2(risks: &Grid<u8>) -> u32 {
+        a_star(&tile(risks, 5)).unwrap().risk
+    }
+}
+
+mod parse {
+    use nom::combinator::verify;
+    use nom::IResult;
+
+    use crate::util::Grid;
+    use crate::util::grid::parse::digits;
+
+    /// Risk levels run from 1 to 9, so a 0 anywhere is rejected.
+    pub(crate) fn risk_map(input: &str) -> IResult<&str, Grid<u8>> {
+        verify(digits, |risks: &Grid<u8>| !risks.cells().contains(&0))(input)
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+
+    const TEST_INPUT: &str = "1163751742
+1381373672
+2136511328
+3694931569
+7463417111
+1319128137
+1359912421
+3125421639
+1293138521
+2311944581";
+
+    #[test]
+    fn test_parse() {
+        let (_, risks) = parse::risk_map(TEST_INPUT).unwrap();
+        assert_eq!((risks.width(), risks.height()), (10, 10));
+        assert_eq!(risks[(9, 0)], 2);
+        assert_eq!(risks[(0, 9)], 2);
+        assert!(Day15::parse("12\n30").is_err());
+    }
+
+    #[test]
+    fn test_lowest_risk() {
+        let (_, risks) = parse::risk_map(TEST_INPUT).unwrap();
+        let path = dijkstra(&risks).unwrap();
+        assert_eq!(path.risk, 40);
+        assert_eq!(a_star(&risks).unwrap().risk, 40);
+    }
+
+    #[test]
+    fn test_path() {
+        let (_, risks) = parse::risk_map(TEST_INPUT).unwrap();
+        let path = dijkstra(&risks).unwrap();
+        assert_eq!(path.steps.first(), Some(&(0, 0)));
+        assert_eq!(path.steps.last(), Some(&(9, 9)));
+        assert_eq!(path.steps[..4], [(0, 0), (0, 1), (0, 2), (1, 2)]);
+        for pair in path.steps.windows(2) {
+            assert!(risks.neighbours4(pair[0]).any(|n| n == pair[1]));
+        }
+        let total: u32 = path.steps[1..].iter().map(|&pos| risks[pos] as u32).sum();
+        assert_eq!(total, path.risk);
+    }
+
+    #[test]
+    fn test_tile() {
+        let (_, risks) = parse::risk_map("8").unwrap();
+        let full = tile(&risks, 5);
+        assert_eq!(full.row(0), [8, 9, 1, 2, 3]);
+        assert_eq!(full.row(4), [3, 4, 5, 6, 7]);
+    }
+
+    #[test]
+    fn test_full_map() {
+        let (_, risks) = parse::risk_map(TEST_INPUT).unwrap();
+        let full = tile(&risks, 5);
+        assert_eq!((full.width(), full.height()), (50, 50));
+        assert_eq!(full.row(49)[40..], [1, 2, 9, 9, 8, 3, 3, 4, 7, 9]);
+        assert_eq!(dijkstra(&full).unwrap().risk, 315);
+        assert_eq!(a_star(&full).unwrap().risk, 315);
+    }
+}
@@ -0,0 +1,358 @@
+use std::fmt::{Display, Formatter};
+
+use crate::Solution;
+use crate::util::{parse_all, ParseError};
+
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum Op {
+    Sum,
+    Product,
+    Minimum,
+    Maximum,
+    GreaterThan,
+    LessThan,
+    EqualTo,
+}
+
+impl Op {
+    /// The operator for a packet type id, or `None` for literals (type 4) and ids that don't fit in three bits.
+    fn from_type_id(id: u8) -> Option<Op> {
+        match id {
+            0 => Some(Op::Sum),
+            1 => Some(Op::Product),
+            2 => Some(Op::Minimum),
+            3 => Some(Op::Maximum),
+            5 => Some(Op::GreaterThan),
+            6 => Some(Op::LessThan),
+            7 => Some(Op::EqualTo),
+            _ => None
+        }
+    }
+
+    fn accepts(&self, operands: usize) -> bool {
+        match self {
+            Op::GreaterThan | Op::LessThan | Op::EqualTo => operands == 2,
+            _ => operands > 0
+        }
+    }
+
+    /// Applies the operator to its operands' values, or `None` if a sum or product overflows.
+    fn apply(&self, values: &[u64]) -> Option<u64> {
+        let mut values = values.iter().copied();
+        Some(match self {
+            Op::Sum => values.try_fold(0u64, u64::checked_add)?,
+            Op::Product => values.try_fold(1u64, u64::checked_mul)?,
+            Op::Minimum => values.min()?,
+            Op::Maximum => values.max()?,
+            Op::GreaterThan => (values.next() > values.next()) as u64,
+            Op::LessThan => (values.next() < values.next()) as u64,
+            Op::EqualTo => (values.next() == values.next()) as u64,
+        })
+    }
+
+    fn symbol(&self) -> &'static str {
+        match self {
+            Op::Sum => "+",
+            Op::Product => "*",
+            Op::Minimum => "min",
+            Op::Maximum => "max",
+            Op::GreaterThan => ">",
+            Op::LessThan => "<",
+            Op::EqualTo => "=",
+        }
+    }
+}
+
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub enum Packet {
+    Literal { version: u8, value: u64 },
+    Operator { version: u8, op: Op, packets: Vec<Packet> },
+}
+
+impl Packet {
+    pub fn version(&self) -> u8 {
+        match self {
+            Packet::Literal { version, .. } | Packet::Operator { version, .. } => *version
+        }
+    }
+
+    /// Sum of the version numbers of this packet and every packet inside it.
+    pub fn version_sum(&self) -> u32 {
+        match self {
+            Packet::Literal { version, .. } => *version as u32,
+            Packet::Operator { version, packets, .. } =>
+                *version as u32 + packets.iter().map(Packet::version_sum).sum::<u32>()
+        }
+    }
+
+    /// The value of the expression, or `None` if a sum or product along the way overflows.
+    pub fn evaluate(&self) -> Option<u64> {
+        let (op, packets) = match self {
+            Packet::Literal { value, .. } => return Some(*value),
+            Packet::Operator { op, packets, .. } => (op, packets),
+        };
+        let values = packets.iter().map(Packet::evaluate).collect::<Option<Vec<u64>>>()?;
+        op.apply(&values)
+    }
+
+    fn write_sexpr(&self, f: &mut Formatter<'_>, indent: Option<usize>) -> std::fmt::Result {
+        let (op, packets) = match self {
+            Packet::Literal { value, .. } => return write!(f, "{}", value),
+            Packet::Operator { op, packets, .. } => (op, packets),
+        };
+        write!(f, "({}", op.symbol())?;
+        let flat = packets.iter().all(|p| matches!(p, Packet::Literal { .. }));
+        for packet in packets {
+            match indent {
+                Some(depth) if !flat => {
+                    write!(f, "\n{:width$}", "", width = 2 * (depth + 1))?;
+                    packet.write_sexpr(f, Some(depth + 1))?;
+                }
+                _ => {
+                    write!(f, " ")?;
+                    packet.write_sexpr(f, indent)?;
+                }
+            }
+        }
+        write!(f, ")")
+    }
+}
+
+/// Prints the expression as an s-expression, such as `(= (+ 1 3) (* 2 2))`. The alternate form (`{:#}`) puts each
+/// operand that isn't a plain number on its own indented line.
+impl Display for Packet {
+    fn fmt(&self, f: &mut Formatter<'_>) -> std::fmt::Result {
+        self.write_sexpr(f, if f.alternate() { Some(0) } else { None })
+    }
+}
+
+pub struct Day16;
+
+impl Solution for Day16 {
+    const DAY: u32 = 16;
+
+    /// The outermost packet, along with its value, worked out as it's parsed.
+    type Input = (Packet, u64);
+    type Answer1 = u32;
+    type Answer2 = u64;
+
+    fn parse(input: &str) -> Result<(Packet, u64), ParseError> {
+        parse_all(Self::DAY, input, parse::transmission)
+    }
+
+    fn part1((packet, _): &(Packet, u64)) -> u32 {
+        packet.version_sum()
+    }
+
+    fn part2((_, value): &(Packet, u64)) -> u64 {
+        *value
+    }
+}
+
+mod parse {
+    use nom::bits::complete::take;
+    use nom::character::complete::hex_digit1;
+    use nom::combinator::map;
+    use nom::error::{Error, ErrorKind};
+    use nom::IResult;
+    use nom::multi::count;
+
+    use super::*;
+
+    /// Input to the bit-level parsers: the bytes, and how many bits of the first byte are already used.
+    type Bits<'a> = (&'a [u8], usize);
+
+    fn remaining((bytes, offset): Bits) -> usize {
+        bytes.len() * 8 - offset
+    }
+
+    fn fail<T>(input: Bits, kind: ErrorKind) -> IResult<Bits, T> {
+        Err(nom::Err::Error(Error::new(input, kind)))
+    }
+
+    fn literal(mut input: Bits) -> IResult<Bits, u64> {
+        let mut value: u64 = 0;
+        loop {
+            if value >> 60 != 0 {
+                return fail(input, ErrorKind::TooLarge);
+            }
+            let (rest, more): (_, u8) = take(1usize)(input)?;
+            let (rest, group): (_, u64) = take(4usize)(rest)?;
+            value = value << 4 | group;
+            input = rest;
+            if more == 0 {
+                return Ok((input, value));
+            }
+        }
+    }
+
+    /// Operands, either as a total length in bits (length type 0) or as a number of packets (length type 1).
+    fn operands(input: Bits) -> IResult<Bits, Vec<(Packet, u64)>> {
+        let (input, length_type): (_, u8) = take(1usize)(input)?;
+        if length_type == 1 {
+            let (input, n): (_, usize) = take(11usize)(input)?;
+            return count(packet, n)(input);
+        }
+
+        let (mut input, length): (_, usize) = take(15usize)(input)?;
+        let end = match remaining(input).checked_sub(length) {
+            Some(end) => end,
+            None => return fail(input, ErrorKind::Eof),
+        };
+        let mut packets = Vec::new();
+        while remaining(input) > end {
+            let (rest, packet) = packet(input)?;
+            packets.push(packet);
+            input = rest;
+        }
+        if remaining(input) != end {
+            return fail(input, ErrorKind::LengthValue);
+        }
+        Ok((input, packets))
+    }
+
+    /// A packet and everything inside it, with its value. Each operator's value is worked out from its operands' as
+    /// soon as they're read, so a value that overflows is caught at the packet that overflows it. Errors point at the
+    /// start of the innermost packet that couldn't be read, and are made failures so the packets around it pass them
+    /// on untouched.
+    fn packet(input: Bits) -> IResult<Bits, (Packet, u64)> {
+        packet_contents(input).map_err(|e| match e {
+            nom::Err::Error(e) => nom::Err::Failure(Error::new(input, e.code)),
+            other => other,
+        })
+    }
+
+    fn packet_contents(start: Bits) -> IResult<Bits, (Packet, u64)> {
+        let (input, version): (_, u8) = take(3usize)(start)?;
+        let (input, type_id): (_, u8) = take(3usize)(input)?;
+        let op = match Op::from_type_id(type_id) {
+            Some(op) => op,
+            None => return map(literal, |value| (Packet::Literal { version, value }, value))(input),
+        };
+        let (rest, operands) = operands(input)?;
+        if !op.accepts(operands.len()) {
+            return fail(start, ErrorKind::Verify);
+        }
+        let (packets, values): (Vec<Packet>, Vec<u64>) = operands.into_iter().unzip();
+        match op.apply(&values) {
+            Some(value) => Ok((rest, (Packet::Operator { version, op, packets }, value))),
+            None => fail(start, ErrorKind::TooLarge),
+        }
+    }
+
+    fn hex_bytes(input: &str) -> IResult<&str, Vec<u8>> {
+        map(hex_digit1, |hex: &str| {
+            hex.as_bytes().chunks(2).map(|pair| {
+                let nibble = |b: &u8| (*b as char).to_digit(16).unwrap() as u8;
+                nibble(&pair[0]) << 4 | pair.get(1).map_or(0, nibble)
+            }).collect()
+        })(input)
+    }
+
+    /// A hex-encoded transmission holding one outermost packet; anything after it is padding. A packet that can't be
+    /// read is reported at the hex digit it starts in.
+    pub(crate) fn transmission(input: &str) -> IResult<&str, (Packet, u64)> {
+        let (rest, bytes) = hex_bytes(input)?;
+        match packet((&bytes, 0)) {
+            Ok((_, packet)) => Ok((rest, packet)),
+            Err(nom::Err::Error(e) | nom::Err::Failure(e)) => {
+                let digit = ((bytes.len() * 8 - remaining(e.input)) / 4).min(input.len() - rest.len());
+                // Running out of bits means the transmission was cut short, not that it should have ended
+                let code = if e.code == ErrorKind::Eof { ErrorKind::Complete } else { e.code };
+                Err(nom::Err::Error(Error::new(&input[digit..], code)))
+            }
+            Err(nom::Err::Incomplete(needed)) => Err(nom::Err::Incomplete(needed)),
+        }
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+
+    fn decode(hex: &str) -> Packet {
+        parse::transmission(hex).unwrap().1.0
+    }
+
+    fn value(hex: &str) -> u64 {
+        parse::transmission(hex).unwrap().1.1
+    }
+
+    #[test]
+    fn test_parse() {
+        assert_eq!(decode("D2FE28"), Packet::Literal { version: 6, value: 2021 });
+        assert_eq!(decode("38006F45291200"), Packet::Operator {
+            version: 1,
+            op: Op::LessThan,
+            packets: vec![Packet::Literal { version: 6, value: 10 }, Packet::Literal { version: 2, value: 20 }],
+        });
+
+        let packet = decode("EE00D40C823060");
+        assert_eq!(packet.version(), 7);
+        assert_eq!(packet.to_string(), "(max 1 2 3)");
+    }
+
+    #[test]
+    fn test_parse_malformed() {
+        assert!(parse::transmission("D2FE").is_err());
+        assert!(parse::transmission("xyz").is_err());
+        // A comparison with three operands
+        assert!(Day16::parse("DE00D40C823060").is_err());
+    }
+
+    #[test]
+    fn test_parse_error_position() {
+        let err = Day16::parse("D2FE").unwrap_err();
+        assert_eq!((err.column, err.expected.as_str()), (1, "more input"));
+
+        // The second of three literals is cut short, so the error points at where it starts
+        let err = Day16::parse("EE00D40C").unwrap_err();
+        assert_eq!((err.column, err.snippet.as_str()), (8, "C"));
+
+        let err = Day16::parse("DE00D40C823060").unwrap_err();
+        assert_eq!(err.column, 1);
+    }
+
+    #[test]
+    fn test_overflow_rejected() {
+        // The product of two literals of 2^40
+        let err = Day16::parse("0600848C2108421084002461084210842000").unwrap_err();
+        assert_eq!((err.column, err.expected.as_str()), (1, "a smaller value"));
+        // The same product inside a sum, starting four and a half digits in
+        assert_eq!(Day16::parse("0200418021230842108421000918421084210800").unwrap_err().column, 5);
+        assert_eq!(value("060084C421084200131084210800"), 1 << 62);
+    }
+
+    #[test]
+    fn test_version_sum() {
+        assert_eq!(decode("8A004A801A8002F478").version_sum(), 16);
+        assert_eq!(decode("620080001611562C8802118E34").version_sum(), 12);
+        assert_eq!(decode("C0015000016115A2E0802F182340").version_sum(), 23);
+        assert_eq!(decode("A0016C880162017C3686B18A3D4780").version_sum(), 31);
+    }
+
+    #[test]
+    fn test_evaluate() {
+        for (hex, expected) in [
+            ("C200B40A82", 3),
+            ("04005AC33890", 54),
+            ("880086C3E88112", 7),
+            ("CE00C43D881120", 9),
+            ("D8005AC2A8F0", 1),
+            ("F600BC2D8F", 0),
+            ("9C005AC2F8F0", 0),
+            ("9C0141080250320F1802104A08", 1),
+        ] {
+            assert_eq!(value(hex), expected);
+            assert_eq!(decode(hex).evaluate(), Some(expected));
+        }
+    }
+
+    #[test]
+    fn test_sexpr() {
+        let packet = decode("9C0141080250320F1802104A08");
+        assert_eq!(packet.to_string(), "(= (+ 1 3) (* 2 2))");
+        assert_eq!(format!("{:#}", packet), "(=\n  (+ 1 3)\n  (* 2 2))");
+        assert_eq!(format!("{:#}", decode("D2FE28")), "2021");
+    }
+}
@@ -0,0 +1,281 @@
+use std::ops::RangeInclusive;
+
+use crate::Solution;
+use crate::util::{parse_all, ParseError};
+
+pub type Point = (i32, i32);
+
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub struct Target {
+    pub x_min: i32,
+    pub x_max: i32,
+    pub y_min: i32,
+    pub y_max: i32,
+}
+
+/// The smallest speed that, slowed by drag, still carries the probe `distance` along, which is the smallest `n` whose
+/// triangular number `n(n+1)/2` reaches it.
+fn min_speed(distance: i32) -> i32 {
+    let mut n = ((8.0 * distance as f64 + 1.0).sqrt() as i32 - 1) / 2;
+    while n * (n + 1) / 2 < distance {
+        n += 1;
+    }
+    n
+}
+
+impl Target {
+    pub fn contains(&self, (x, y): Point) -> bool {
+        (self.x_min..=self.x_max).contains(&x) && (self.y_min..=self.y_max).contains(&y)
+    }
+
+    /// Whether a probe at `pos` moving at `velocity` has missed for good.
+    fn out_of_reach(&self, (x, y): Point, (vx, vy): Point) -> bool {
+        (y < self.y_min && vy < 0)
+            || (vx >= 0 && x > self.x_max)
+            || (vx <= 0 && x < self.x_min)
+    }
+
+    /// Whether infinitely many velocities hit: a target that spans `y = 0`, with some horizontal speed that drag brings
+    /// to a stop inside it. However high a probe is thrown up, it comes back down through `y = 0`, and thrown high
+    /// enough it has stopped moving sideways by then.
+    pub fn unbounded(&self) -> bool {
+        let (vxs, _) = self.velocity_bounds();
+        let spans_launch_height = self.y_min <= 0 && 0 <= self.y_max;
+        spans_launch_height && vxs.into_iter().any(|vx| (self.x_min..=self.x_max).contains(&stops_at(vx)))
+    }
+
+    /// Every initial velocity that could possibly hit lies within these ranges, unless the target is
+    /// [`unbounded`](Target::unbounded).
+    ///
+    /// Horizontally the probe must at least reach the near edge before drag stops it, and must not jump straight past
+    /// the far edge on its first step. Vertically it mustn't drop straight below the target on its first step; and a
+    /// probe thrown upwards at `vy` comes back down through `y = 0` at `-(vy + 1)`, so that next step mustn't overshoot
+    /// either. A target above the start can't be hit going up faster than its top edge, as the descent retraces the
+    /// ascent. A target spanning `y = 0` can also be hit on the way back through it, but only while the probe is still
+    /// moving sideways, which it can't be for more steps than its horizontal speed.
+    pub fn velocity_bounds(&self) -> (RangeInclusive<i32>, RangeInclusive<i32>) {
+        let vx = if self.x_min > 0 {
+            min_speed(self.x_min)..=self.x_max
+        } else if self.x_max < 0 {
+            self.x_min..=-min_speed(-self.x_max)
+        } else {
+            self.x_min..=self.x_max
+        };
+        let mut vy_max = self.y_max.max(-self.y_min - 1);
+        if self.y_min <= 0 && 0 <= self.y_max {
+            vy_max = vy_max.max(self.x_min.abs()).max(self.x_max.abs());
+        }
+        let vy = self.y_min.min(0)..=vy_max;
+        (vx, vy)
+    }
+}
+
+/// Where drag brings a probe launched sideways at `vx` to a stop.
+fn stops_at(vx: i32) -> i32 {
+    vx.signum() * vx.abs() * (vx.abs() + 1) / 2
+}
+
+/// A probe in flight, yielding its position after each step.
+pub struct Probe {
+    pub pos: Point,
+    pub velocity: Point,
+}
+
+impl Probe {
+    pub fn launch(velocity: Point) -> Probe {
+        Probe { pos: (0, 0), velocity }
+    }
+}
+
+impl Iterator for Probe {
+    type Item = Point;
+
+    fn next(&mut self) -> Option<Point> {
+        let (vx, vy) = self.velocity;
+        self.pos = (self.pos.0 + vx, self.pos.1 + vy);
+        self.velocity = (vx - vx.signum(), vy - 1);
+        Some(self.pos)
+    }
+}
+
+/// The points a probe passes through, starting at the origin and ending either inside the target or at the last point
+/// before it's clear the probe will miss. Only where it is after a step counts, so the origin is never a hit.
+pub fn trajectory(target: &Target, velocity: Point) -> Vec<Point> {
+    let mut probe = Probe::launch(velocity);
+    let mut points = vec![probe.pos];
+    loop {
+        let pos = probe.next().unwrap();
+        points.push(pos);
+        if target.contains(pos) || target.out_of_reach(pos, probe.velocity) {
+            break points;
+        }
+    }
+}
+
+pub fn hits(target: &Target, velocity: Point) -> bool {
+    let mut probe = Probe::launch(velocity);
+    loop {
+        let pos = probe.next().unwrap();
+        if target.contains(pos) {
+            break true;
+        }
+        if target.out_of_reach(pos, probe.velocity) {
+            break false;
+        }
+    }
+}
+
+/// Every distinct initial velocity that puts the probe in the target at the end of some step, or `None` if there are
+/// infinitely many.
+pub fn hitting_velocities(target: &Target) -> Option<Vec<Point>> {
+    if target.unbounded() {
+        return None;
+    }
+    let (vxs, vys) = target.velocity_bounds();
+    Some(vxs.flat_map(|vx| vys.clone().map(move |vy| (vx, vy)))
+        .filter(|&velocity| hits(target, velocity))
+        .collect())
+}
+
+/// How high a probe launched at `velocity` climbs.
+pub fn apex((_, vy): Point) -> i32 {
+    if vy > 0 { vy * (vy + 1) / 2 } else { 0 }
+}
+
+const UNBOUNDED: &str = "unbounded, the target spans the launch height";
+
+pub struct Day17;
+
+impl Solution for Day17 {
+    const DAY: u32 = 17;
+
+    type Input = Target;
+    type Answer1 = String;
+    type Answer2 = String;
+
+    fn parse(input: &str) -> Result<Target, ParseError> {
+        parse_all(Self::DAY, input, parse::target)
+    }
+
+    fn part1(target: &Target) -> String {
+        hitting_velocities(target)
+            .map_or(UNBOUNDED.to_owned(), |v| v.into_iter().map(apex).max().unwrap_or(0).to_string())
+    }
+
+    fn part2(target: &Target) -> String {
+        hitting_velocities(target).map_or(UNBOUNDED.to_owned(), |v| v.len().to_string())
+    }
+}
+
+mod parse {
+    use nom::bytes::complete::tag;
+    use nom::character::complete::i32 as num;
+    use nom::combinator::map_opt;
+    use nom::IResult;
+    use nom::sequence::{preceded, separated_pair};
+
+    use super::*;
+
+    fn range(input: &str) -> IResult<&str, (i32, i32)> {
+        map_opt(separated_pair(num, tag(".."), num), |(lo, hi)| if lo <= hi { Some((lo, hi)) } else { None })(input)
+    }
+
+    pub(crate) fn target(input: &str) -> IResult<&str, Target> {
+        let (input, (x_min, x_max)) = preceded(tag("target area: x="), range)(input)?;
+        let (input, (y_min, y_max)) = preceded(tag(", y="), range)(input)?;
+        Ok((input, Target { x_min, x_max, y_min, y_max }))
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+
+    const TEST_INPUT: &str = "target area: x=20..30, y=-10..-5";
+
+    #[test]
+    fn test_parse() {
+        let (_, target) = parse::target(TEST_INPUT).unwrap();
+        assert_eq!(target, Target { x_min: 20, x_max: 30, y_min: -10, y_max: -5 });
+        assert!(parse::target("target area: x=30..20, y=-10..-5").is_err());
+    }
+
+    #[test]
+    fn test_hits() {
+        let (_, target) = parse::target(TEST_INPUT).unwrap();
+        assert!(hits(&target, (7, 2)));
+        assert!(hits(&target, (6, 3)));
+        assert!(hits(&target, (9, 0)));
+        assert!(!hits(&target, (17, -4)));
+    }
+
+    #[test]
+    fn test_trajectory() {
+        let (_, target) = parse::target(TEST_INPUT).unwrap();
+        assert_eq!(trajectory(&target, (7, 2)),
+                   [(0, 0), (7, 2), (13, 3), (18, 3), (22, 2), (25, 0), (27, -3), (28, -7)]);
+        assert_eq!(trajectory(&target, (17, -4)), [(0, 0), (17, -4), (33, -9)]);
+    }
+
+    #[test]
+    fn test_velocity_bounds() {
+        let (_, target) = parse::target(TEST_INPUT).unwrap();
+        assert_eq!(target.velocity_bounds(), (6..=30, -10..=9));
+        assert_eq!(min_speed(21), 6);
+        assert_eq!(min_speed(22), 7);
+    }
+
+    #[test]
+    fn test_highest_apex() {
+        let (_, target) = parse::target(TEST_INPUT).unwrap();
+        assert_eq!(Day17::part1(&target), "45");
+    }
+
+    #[test]
+    fn test_hitting_velocities() {
+        let (_, target) = parse::target(TEST_INPUT).unwrap();
+        let velocities = hitting_velocities(&target).unwrap();
+        assert_eq!(velocities.len(), 112);
+        assert!(velocities.contains(&(6, 9)) && velocities.contains(&(30, -10)));
+    }
+
+    #[test]
+    fn test_other_quadrants() {
+        let above = Target { x_min: -30, x_max: -20, y_min: 5, y_max: 10 };
+        let velocities = hitting_velocities(&above).unwrap();
+        assert!(!velocities.is_empty());
+        assert!(velocities.iter().all(|&(vx, vy)| vx < 0 && vy > 0));
+        assert!(velocities.contains(&(-6, 4)));
+        assert_eq!(Day17::part1(&above), "55");
+    }
+
+    #[test]
+    fn test_spanning_launch_height() {
+        let level = Target { x_min: 20, x_max: 30, y_min: -5, y_max: 5 };
+        assert!(level.unbounded());
+        assert_eq!(hitting_velocities(&level), None);
+        assert_eq!(Day17::part2(&level), UNBOUNDED);
+        assert!(hits(&level, (6, 1000)));
+
+        // No horizontal speed stops between 11 and 14, so only finitely many velocities get there
+        let gap = Target { x_min: 11, x_max: 14, y_min: -5, y_max: 5 };
+        assert!(!gap.unbounded());
+        let velocities = hitting_velocities(&gap).unwrap();
+        assert_eq!(velocities, brute_force(&gap));
+    }
+
+    #[test]
+    fn test_origin_is_not_a_hit() {
+        let origin = Target { x_min: 0, x_max: 0, y_min: 0, y_max: 0 };
+        assert!(!hits(&origin, (5, 5)));
+        assert!(hits(&origin, (0, 0)));
+        assert!(Target { x_min: -5, x_max: 5, y_min: -5, y_max: 5 }.unbounded());
+    }
+
+    /// Tries every velocity in a wide square, for checking against.
+    fn brute_force(target: &Target) -> Vec<Point> {
+        (-100..=100).flat_map(|vx| (-100..=100).map(move |vy| (vx, vy)))
+            .filter(|&velocity| hits(target, velocity))
+            .collect()
+    }
+}
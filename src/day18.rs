@@ -0,0 +1,255 @@
+use std::fmt::{Display, Formatter};
+use std::ops::Add;
+
+use crate::Solution;
+use crate::util::{parse_all, ParseError};
+
+#[derive(Clone, Copy, Debug, Eq, PartialEq)]
+enum Token {
+    Open,
+    Close,
+    Regular(u32),
+}
+
+/// A snailfish number, kept as a flat run of brackets and regular numbers, the same way day 10 keeps its chunk lines,
+/// since reducing one only ever looks at a pair's neighbours to the left and right.
+#[derive(Clone, Debug, Eq, PartialEq)]
+pub struct SnailfishNumber {
+    tokens: Vec<Token>,
+}
+
+fn add_to_first<'a>(mut tokens: impl Iterator<Item=&'a mut Token>, value: u32) {
+    if let Some(Token::Regular(n)) = tokens.find(|t| matches!(t, Token::Regular(_))) {
+        *n += value;
+    }
+}
+
+impl SnailfishNumber {
+    /// Explodes the leftmost pair nested inside four others, returning whether there was one.
+    fn explode(&mut self) -> bool {
+        let mut depth = 0;
+        for i in 0..self.tokens.len() {
+            match self.tokens[i] {
+                Token::Open => depth += 1,
+                Token::Close => depth -= 1,
+                Token::Regular(_) => continue,
+            }
+            if depth <= 4 { continue; }
+
+            if let [Token::Open, Token::Regular(left), Token::Regular(right), Token::Close, ..] = self.tokens[i..] {
+                add_to_first(self.tokens[..i].iter_mut().rev(), left);
+                add_to_first(self.tokens[i + 4..].iter_mut(), right);
+                self.tokens.splice(i..i + 4, [Token::Regular(0)]);
+                return true;
+            }
+        }
+        false
+    }
+
+    /// Splits the leftmost regular number of 10 or more, returning whether there was one.
+    fn split(&mut self) -> bool {
+        let found = self.tokens.iter().position(|t| matches!(t, Token::Regular(n) if *n >= 10));
+        if let Some(i) = found {
+            let Token::Regular(n) = self.tokens[i] else { unreachable!() };
+            self.tokens.splice(i..=i, [Token::Open, Token::Regular(n / 2), Token::Regular(n.div_ceil(2)), Token::Close]);
+        }
+        found.is_some()
+    }
+
+    fn reduce(&mut self) {
+        while self.explode() || self.split() {}
+    }
+
+    pub fn magnitude(&self) -> u32 {
+        let mut stack = Vec::new();
+        for &token in &self.tokens {
+            match token {
+                Token::Open => {}
+                Token::Regular(n) => stack.push(n),
+                Token::Close => {
+                    let right = stack.pop().unwrap();
+                    let left = stack.pop().unwrap();
+                    stack.push(3 * left + 2 * right);
+                }
+            }
+        }
+        stack.pop().unwrap_or(0)
+    }
+}
+
+impl Add for SnailfishNumber {
+    type Output = SnailfishNumber;
+
+    fn add(self, rhs: SnailfishNumber) -> SnailfishNumber {
+        let mut tokens = Vec::with_capacity(self.tokens.len() + rhs.tokens.len() + 2);
+        tokens.push(Token::Open);
+        tokens.extend(self.tokens);
+        tokens.extend(rhs.tokens);
+        tokens.push(Token::Close);
+
+        let mut sum = SnailfishNumber { tokens };
+        sum.reduce();
+        sum
+    }
+}
+
+impl Display for SnailfishNumber {
+    fn fmt(&self, f: &mut Formatter<'_>) -> std::fmt::Result {
+        let mut after_element = false;
+        for token in &self.tokens {
+            if after_element && token != &Token::Close {
+                write!(f, ",")?;
+            }
+            match token {
+                Token::Open => write!(f, "[")?,
+                Token::Close => write!(f, "]")?,
+                Token::Regular(n) => write!(f, "{}", n)?,
+            }
+            after_element = token != &Token::Open;
+        }
+        Ok(())
+    }
+}
+
+fn sum(numbers: &[SnailfishNumber]) -> Option<SnailfishNumber> {
+    numbers.iter().cloned().reduce(|a, b| a + b)
+}
+
+/// The largest magnitude from adding any two different numbers, in either order, since addition doesn't commute.
+pub fn largest_pair_magnitude(numbers: &[SnailfishNumber]) -> u32 {
+    let mut best = 0;
+    for (i, a) in numbers.iter().enumerate() {
+        for (j, b) in numbers.iter().enumerate() {
+            if i != j {
+                best = best.max((a.clone() + b.clone()).magnitude());
+            }
+        }
+    }
+    best
+}
+
+pub struct Day18;
+
+impl Solution for Day18 {
+    const DAY: u32 = 18;
+
+    type Input = Vec<SnailfishNumber>;
+    type Answer1 = u32;
+    type Answer2 = u32;
+
+    fn parse(input: &str) -> Result<Vec<SnailfishNumber>, ParseError> {
+        parse_all(Self::DAY, input, parse::homework)
+    }
+
+    fn part1(numbers: &Vec<SnailfishNumber>) -> u32 {
+        sum(numbers).map_or(0, |n| n.magnitude())
+    }
+
+    fn part2(numbers: &Vec<SnailfishNumber>) -> u32 {
+        largest_pair_magnitude(numbers)
+    }
+}
+
+mod parse {
+    use nom::branch::alt;
+    use nom::character::complete::{char, newline, u32 as num};
+    use nom::combinator::map;
+    use nom::IResult;
+    use nom::sequence::{delimited, separated_pair};
+
+    use crate::util::lines1;
+
+    use super::*;
+
+    fn element(input: &str) -> IResult<&str, Vec<Token>> {
+        alt((map(num, |n| vec![Token::Regular(n)]), pair))(input)
+    }
+
+    fn pair(input: &str) -> IResult<&str, Vec<Token>> {
+        map(delimited(char('['), separated_pair(element, char(','), element), char(']')), |(left, right)| {
+            let mut tokens = Vec::with_capacity(left.len() + right.len() + 2);
+            tokens.push(Token::Open);
+            tokens.extend(left);
+            tokens.extend(right);
+            tokens.push(Token::Close);
+            tokens
+        })(input)
+    }
+
+    pub(crate) fn number(input: &str) -> IResult<&str, SnailfishNumber> {
+        map(pair, |tokens| SnailfishNumber { tokens })(input)
+    }
+
+    pub(crate) fn homework(input: &str) -> IResult<&str, Vec<SnailfishNumber>> {
+        lines1(newline, number)(input)
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+
+    const TEST_INPUT: &str = "[[[0,[5,8]],[[1,7],[9,6]]],[[4,[1,2]],[[1,4],2]]]
+[[[5,[2,8]],4],[5,[[9,9],0]]]
+[6,[[[6,2],[5,6]],[[7,6],[4,7]]]]
+[[[6,[0,7]],[0,9]],[4,[9,[9,0]]]]
+[[[7,[6,4]],[3,[1,3]]],[[[5,5],1],9]]
+[[6,[[7,3],[3,2]]],[[[3,8],[5,7]],4]]
+[[[[5,4],[7,7]],8],[[8,3],8]]
+[[9,3],[[9,9],[6,[4,9]]]]
+[[2,[[7,7],7]],[[5,8],[[9,3],[0,2]]]]
+[[[[5,2],5],[8,[3,7]]],[[5,[7,5]],[4,4]]]";
+
+    fn number(text: &str) -> SnailfishNumber {
+        parse::number(text).unwrap().1
+    }
+
+    #[test]
+    fn test_parse() {
+        let (_, numbers) = parse::homework(TEST_INPUT).unwrap();
+        assert_eq!(numbers.len(), 10);
+        for (n, line) in numbers.iter().zip(TEST_INPUT.lines()) {
+            assert_eq!(n.to_string(), line);
+        }
+        assert!(parse::number("[1,2").is_err());
+        assert!(parse::number("3").is_err());
+    }
+
+    #[test]
+    fn test_explode() {
+        for (before, after) in [
+            ("[[[[[9,8],1],2],3],4]", "[[[[0,9],2],3],4]"),
+            ("[7,[6,[5,[4,[3,2]]]]]", "[7,[6,[5,[7,0]]]]"),
+            ("[[6,[5,[4,[3,2]]]],1]", "[[6,[5,[7,0]]],3]"),
+            ("[[3,[2,[1,[7,3]]]],[6,[5,[4,[3,2]]]]]", "[[3,[2,[8,0]]],[9,[5,[4,[3,2]]]]]"),
+        ] {
+            let mut n = number(before);
+            assert!(n.explode());
+            assert_eq!(n.to_string(), after);
+        }
+    }
+
+    #[test]
+    fn test_add() {
+        let sum = number("[[[[4,3],4],4],[7,[[8,4],9]]]") + number("[1,1]");
+        assert_eq!(sum.to_string(), "[[[[0,7],4],[[7,8],[6,0]]],[8,1]]");
+
+        let numbers: Vec<_> = ["[1,1]", "[2,2]", "[3,3]", "[4,4]", "[5,5]", "[6,6]"].map(number).into();
+        assert_eq!(super::sum(&numbers).unwrap().to_string(), "[[[[5,0],[7,4]],[5,5]],[6,6]]");
+    }
+
+    #[test]
+    fn test_magnitude() {
+        assert_eq!(number("[[1,2],[[3,4],5]]").magnitude(), 143);
+        assert_eq!(number("[[[[8,7],[7,7]],[[8,6],[7,7]]],[[[0,7],[6,6]],[8,7]]]").magnitude(), 3488);
+    }
+
+    #[test]
+    fn test_homework() {
+        let (_, numbers) = parse::homework(TEST_INPUT).unwrap();
+        let total = sum(&numbers).unwrap();
+        assert_eq!(total.to_string(), "[[[[6,6],[7,6]],[[7,7],[7,0]]],[[[7,7],[7,7]],[[7,8],[9,9]]]]");
+        assert_eq!(total.magnitude(), 4140);
+        assert_eq!(largest_pair_magnitude(&numbers), 3993);
+    }
+}
@@ -0,0 +1,296 @@
+use std::collections::{HashMap, HashSet};
+
+use crate::Solution;
+use crate::util::{parse_all, ParseError};
+
+pub type Vec3 = [i32; 3];
+
+/// How many beacons two scanners must both see before their reports are trusted to line up.
+const OVERLAP: usize = 12;
+
+fn add(a: Vec3, b: Vec3) -> Vec3 {
+    [a[0] + b[0], a[1] + b[1], a[2] + b[2]]
+}
+
+fn sub(a: Vec3, b: Vec3) -> Vec3 {
+    [a[0] - b[0], a[1] - b[1], a[2] - b[2]]
+}
+
+pub fn manhattan(a: Vec3, b: Vec3) -> i32 {
+    sub(a, b).iter().map(|d| d.abs()).sum()
+}
+
+/// One of the 24 ways a scanner can be turned while still facing along an axis, as a matrix of 0s and ±1s.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub struct Rotation([[i32; 3]; 3]);
+
+impl Rotation {
+    /// All 24 rotations: the signed axis permutations that don't also mirror.
+    pub fn all() -> Vec<Rotation> {
+        const PERMUTATIONS: [[usize; 3]; 6] = [[0, 1, 2], [0, 2, 1], [1, 0, 2], [1, 2, 0], [2, 0, 1], [2, 1, 0]];
+        let mut rotations = Vec::with_capacity(24);
+        for perm in PERMUTATIONS {
+            for signs in 0..8 {
+                let mut m = [[0; 3]; 3];
+                for (row, &axis) in perm.iter().enumerate() {
+                    m[row][axis] = if signs & (1 << row) == 0 { 1 } else { -1 };
+                }
+                let rotation = Rotation(m);
+                if rotation.determinant() == 1 {
+                    rotations.push(rotation);
+                }
+            }
+        }
+        rotations
+    }
+
+    fn determinant(&self) -> i32 {
+        let m = &self.0;
+        m[0][0] * (m[1][1] * m[2][2] - m[1][2] * m[2][1])
+            - m[0][1] * (m[1][0] * m[2][2] - m[1][2] * m[2][0])
+            + m[0][2] * (m[1][0] * m[2][1] - m[1][1] * m[2][0])
+    }
+
+    pub fn apply(&self, p: Vec3) -> Vec3 {
+        self.0.map(|row| row[0] * p[0] + row[1] * p[1] + row[2] * p[2])
+    }
+}
+
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct Scanner {
+    pub id: u32,
+    /// Beacons relative to the scanner, in its own orientation.
+    pub beacons: Vec<Vec3>,
+}
+
+impl Scanner {
+    /// Squared distances between every pair of beacons, sorted. These don't change however the scanner is turned or
+    /// moved, so two scanners sharing a dozen beacons must share at least 66 of them.
+    fn fingerprint(&self) -> Vec<i64> {
+        let mut distances = Vec::new();
+        for (i, a) in self.beacons.iter().enumerate() {
+            for b in &self.beacons[i + 1..] {
+                distances.push(sub(*a, *b).iter().map(|&d| (d as i64).pow(2)).sum());
+            }
+        }
+        distances.sort_unstable();
+        distances
+    }
+}
+
+/// How many distances two fingerprints have in common, counting repeats.
+fn shared(a: &[i64], b: &[i64]) -> usize {
+    let (mut i, mut j, mut count) = (0, 0, 0);
+    while i < a.len() && j < b.len() {
+        match a[i].cmp(&b[j]) {
+            std::cmp::Ordering::Less => i += 1,
+            std::cmp::Ordering::Greater => j += 1,
+            std::cmp::Ordering::Equal => { count += 1; i += 1; j += 1; }
+        }
+    }
+    count
+}
+
+/// Finds the rotation and offset that put at least [`OVERLAP`] of `beacons` on top of beacons in `known`.
+fn align(known: &[Vec3], beacons: &[Vec3], rotations: &[Rotation]) -> Option<(Rotation, Vec3)> {
+    for &rotation in rotations {
+        let mut votes = HashMap::new();
+        for &b in beacons {
+            let turned = rotation.apply(b);
+            for &k in known {
+                let count = votes.entry(sub(k, turned)).or_insert(0);
+                *count += 1;
+                if *count >= OVERLAP {
+                    return Some((rotation, sub(k, turned)));
+                }
+            }
+        }
+    }
+    None
+}
+
+/// Every beacon and scanner, positioned relative to the first scanner and in its orientation.
+#[derive(Debug)]
+pub struct Map {
+    pub beacons: HashSet<Vec3>,
+    /// Scanner positions, in the same order as the reports.
+    pub scanners: Vec<Vec3>,
+}
+
+impl Map {
+    pub fn max_scanner_distance(&self) -> i32 {
+        let pairs = self.scanners.iter().flat_map(|&a| self.scanners.iter().map(move |&b| manhattan(a, b)));
+        pairs.max().unwrap_or(0)
+    }
+}
+
+/// Lines the scanners up one at a time, each against one already placed, or `None` if some scanner never overlaps
+/// the rest.
+pub fn assemble(scanners: &[Scanner]) -> Option<Map> {
+    let rotations = Rotation::all();
+    let prints: Vec<Vec<i64>> = scanners.iter().map(Scanner::fingerprint).collect();
+    let needed = OVERLAP * (OVERLAP - 1) / 2;
+
+    let mut placed: Vec<Option<(Vec3, Vec<Vec3>)>> = vec![None; scanners.len()];
+    placed[0] = Some(([0, 0, 0], scanners.first()?.beacons.clone()));
+    let mut frontier = vec![0];
+    while let Some(i) = frontier.pop() {
+        for j in 0..scanners.len() {
+            if placed[j].is_some() || shared(&prints[i], &prints[j]) < needed { continue; }
+
+            let known = &placed[i].as_ref().unwrap().1;
+            if let Some((rotation, offset)) = align(known, &scanners[j].beacons, &rotations) {
+                let beacons = scanners[j].beacons.iter().map(|&b| add(rotation.apply(b), offset)).collect();
+                placed[j] = Some((offset, beacons));
+                frontier.push(j);
+            }
+        }
+    }
+
+    let placed: Vec<(Vec3, Vec<Vec3>)> = placed.into_iter().collect::<Option<_>>()?;
+    Some(Map {
+        beacons: placed.iter().flat_map(|(_, beacons)| beacons.iter().copied()).collect(),
+        scanners: placed.iter().map(|&(pos, _)| pos).collect(),
+    })
+}
+
+pub struct Day19;
+
+impl Solution for Day19 {
+    const DAY: u32 = 19;
+
+    type Input = Vec<Scanner>;
+    type Answer1 = usize;
+    type Answer2 = i32;
+
+    fn parse(input: &str) -> Result<Vec<Scanner>, ParseError> {
+        parse_all(Self::DAY, input, parse::reports)
+    }
+
+    fn part1(scanners: &Vec<Scanner>) -> usize {
+        assemble(scanners).expect("scanners don't all overlap").beacons.len()
+    }
+
+    fn part2(scanners: &Vec<Scanner>) -> i32 {
+        assemble(scanners).expect("scanners don't all overlap").max_scanner_distance()
+    }
+}
+
+mod parse {
+    use nom::bytes::complete::tag;
+    use nom::character::complete::{char, i32 as num, newline, u32 as id};
+    use nom::combinator::map;
+    use nom::IResult;
+    use nom::multi::many1;
+    use nom::sequence::{delimited, pair, terminated, tuple};
+
+    use crate::util::lines1;
+
+    use super::*;
+
+    fn beacon(input: &str) -> IResult<&str, Vec3> {
+        map(tuple((terminated(num, char(',')), terminated(num, char(',')), num)), |(x, y, z)| [x, y, z])(input)
+    }
+
+    fn scanner(input: &str) -> IResult<&str, Scanner> {
+        map(pair(delimited(tag("--- scanner "), id, tag(" ---\n")), lines1(newline, beacon)),
+            |(id, beacons)| Scanner { id, beacons })(input)
+    }
+
+    pub(crate) fn reports(input: &str) -> IResult<&str, Vec<Scanner>> {
+        lines1(many1(newline), scanner)(input)
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+
+    const TEST_INPUT: &str = "--- scanner 0 ---
+404,-588,-901
+528,-643,409
+
+--- scanner 1 ---
+686,422,578
+-336,658,858";
+
+    /// Builds reports for scanners at `positions`, each turned by the matching rotation, that see every beacon within
+    /// 1000 of them along each axis. Consecutive scanners that are close enough get an extra dozen beacons they share.
+    fn survey(positions: &[Vec3], turns: &[usize]) -> (Vec<Scanner>, HashSet<Vec3>) {
+        let mut seed: u64 = 19;
+        let mut random = |lo: i32, hi: i32| {
+            seed = seed.wrapping_mul(6364136223846793005).wrapping_add(1442695040888963407);
+            lo + ((seed >> 33) % (hi - lo + 1) as u64) as i32
+        };
+
+        let mut beacons = HashSet::new();
+        for (i, &pos) in positions.iter().enumerate() {
+            for _ in 0..15 {
+                beacons.insert([0, 1, 2].map(|axis| pos[axis] + random(-1000, 1000)));
+            }
+            let Some(&next) = positions.get(i + 1) else { continue };
+            let bounds = [0, 1, 2].map(|axis| (pos[axis].max(next[axis]) - 1000, pos[axis].min(next[axis]) + 1000));
+            if bounds.iter().all(|(lo, hi)| lo <= hi) {
+                for _ in 0..OVERLAP {
+                    beacons.insert(bounds.map(|(lo, hi)| random(lo, hi)));
+                }
+            }
+        }
+
+        let rotations = Rotation::all();
+        let scanners = positions.iter().zip(turns).enumerate().map(|(id, (&pos, &turn))| {
+            let Rotation(m) = rotations[turn];
+            let inverse = Rotation([0, 1, 2].map(|r| [m[0][r], m[1][r], m[2][r]]));
+            let seen = beacons.iter().filter(|&&b| chebyshev(b, pos) <= 1000);
+            Scanner { id: id as u32, beacons: seen.map(|&b| inverse.apply(sub(b, pos))).collect() }
+        }).collect();
+        (scanners, beacons)
+    }
+
+    fn chebyshev(a: Vec3, b: Vec3) -> i32 {
+        sub(a, b).iter().map(|d| d.abs()).max().unwrap()
+    }
+
+    #[test]
+    fn test_parse() {
+        let (_, scanners) = parse::reports(TEST_INPUT).unwrap();
+        assert_eq!(scanners.len(), 2);
+        assert_eq!(scanners[1], Scanner { id: 1, beacons: vec![[686, 422, 578], [-336, 658, 858]] });
+    }
+
+    #[test]
+    fn test_rotations() {
+        let rotations = Rotation::all();
+        assert_eq!(rotations.len(), 24);
+        assert!(rotations.contains(&Rotation([[1, 0, 0], [0, 1, 0], [0, 0, 1]])));
+
+        let turned: HashSet<Vec3> = rotations.iter().map(|r| r.apply([1, 2, 3])).collect();
+        assert_eq!(turned.len(), 24);
+        assert!(!turned.contains(&[-1, 2, 3]));
+    }
+
+    #[test]
+    fn test_fingerprint() {
+        let a = Scanner { id: 0, beacons: vec![[0, 0, 0], [1, 2, 3], [4, 0, 0]] };
+        let b = Scanner { id: 1, beacons: a.beacons.iter().map(|&p| add(Rotation::all()[7].apply(p), [5, 5, 5])).collect() };
+        assert_eq!(a.fingerprint(), [14, 16, 22]);
+        assert_eq!(shared(&a.fingerprint(), &b.fingerprint()), 3);
+    }
+
+    #[test]
+    fn test_assemble() {
+        let positions = [[0, 0, 0], [1150, -40, 60], [1200, 1100, -80], [2300, 1000, 40]];
+        let (scanners, beacons) = survey(&positions, &[0, 5, 13, 22]);
+
+        let map = assemble(&scanners).unwrap();
+        assert_eq!(map.scanners, positions);
+        assert_eq!(map.beacons, beacons);
+        assert_eq!(map.max_scanner_distance(), 2300 + 1000 + 40);
+    }
+
+    #[test]
+    fn test_no_overlap() {
+        let (scanners, _) = survey(&[[0, 0, 0], [5000, 0, 0]], &[0, 3]);
+        assert!(assemble(&scanners).is_none());
+    }
+}
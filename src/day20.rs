@@ -0,0 +1,194 @@
+use std::fmt::{Display, Formatter};
+use std::iter::successors;
+
+use crate::Solution;
+use crate::util::{Grid, parse_all, ParseError};
+
+/// Whether each 3x3 neighbourhood, read as a 9-bit number from the top left, lights the pixel in the middle.
+pub type Algorithm = [bool; 512];
+
+/// A finite patch of lit and dark pixels on an infinite canvas, where everything outside the patch is `background`.
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct Image {
+    pixels: Grid<bool>,
+    background: bool,
+}
+
+impl Image {
+    fn get(&self, x: isize, y: isize) -> bool {
+        if x < 0 || y < 0 {
+            return self.background;
+        }
+        self.pixels.get((x as usize, y as usize)).copied().unwrap_or(self.background)
+    }
+
+    /// Applies the algorithm once. Only pixels next to the patch can differ from the background, so the patch grows
+    /// by one on every side; the background itself flips whenever a dark neighbourhood lights a pixel (`algorithm[0]`)
+    /// and a lit one doesn't (`algorithm[511]`).
+    pub fn enhance(&self, algorithm: &Algorithm) -> Image {
+        let (width, height) = (self.pixels.width() + 2, self.pixels.height() + 2);
+        let mut pixels = Grid::filled(width, height, false);
+        for (x, y) in pixels.positions() {
+            let mut index = 0;
+            for dy in -1..=1 {
+                for dx in -1..=1 {
+                    let lit = self.get(x as isize - 1 + dx, y as isize - 1 + dy);
+                    index = index << 1 | lit as usize;
+                }
+            }
+            pixels[(x, y)] = algorithm[index];
+        }
+        let background = algorithm[if self.background { 511 } else { 0 }];
+        Image { pixels, background }
+    }
+
+    /// How many pixels are lit, or `None` if the background is lit and so infinitely many are.
+    pub fn lit(&self) -> Option<usize> {
+        if self.background {
+            None
+        } else {
+            Some(self.pixels.cells().iter().filter(|&&lit| lit).count())
+        }
+    }
+}
+
+impl Display for Image {
+    fn fmt(&self, f: &mut Formatter<'_>) -> std::fmt::Result {
+        for row in self.pixels.rows() {
+            for &lit in row {
+                write!(f, "{}", if lit { '#' } else { '.' })?;
+            }
+            writeln!(f)?;
+        }
+        Ok(())
+    }
+}
+
+/// The image before any enhancement, then after each pass in turn.
+pub fn passes<'a>(algorithm: &'a Algorithm, image: &Image) -> impl Iterator<Item=Image> + 'a {
+    successors(Some(image.clone()), move |image| Some(image.enhance(algorithm)))
+}
+
+fn lit_after(algorithm: &Algorithm, image: &Image, count: usize) -> usize {
+    let enhanced = passes(algorithm, image).nth(count).unwrap();
+    enhanced.lit().expect("infinitely many pixels are lit")
+}
+
+pub struct Day20;
+
+impl Solution for Day20 {
+    const DAY: u32 = 20;
+
+    type Input = (Algorithm, Image);
+    type Answer1 = usize;
+    type Answer2 = usize;
+
+    fn parse(input: &str) -> Result<(Algorithm, Image), ParseError> {
+        parse_all(Self::DAY, input, parse::trench_map)
+    }
+
+    fn part1((algorithm, image): &(Algorithm, Image)) -> usize {
+        lit_after(algorithm, image, 2)
+    }
+
+    fn part2((algorithm, image): &(Algorithm, Image)) -> usize {
+        lit_after(algorithm, image, 50)
+    }
+
+    fn frames((algorithm, image): &(Algorithm, Image), limit: usize) -> Vec<String> {
+        passes(algorithm, image).take(limit).enumerate().map(|(pass, image)| {
+            let lit = image.lit().map_or("infinitely many".to_owned(), |n| n.to_string());
+            format!("pass {}, {} lit\n{}", pass, lit, image)
+        }).collect()
+    }
+}
+
+mod parse {
+    use nom::branch::alt;
+    use nom::bytes::complete::tag;
+    use nom::character::complete::{char, newline};
+    use nom::combinator::{map, map_opt, value};
+    use nom::IResult;
+    use nom::multi::{many1, separated_list1};
+    use nom::sequence::separated_pair;
+
+    use super::*;
+
+    fn pixel(input: &str) -> IResult<&str, bool> {
+        alt((value(true, char('#')), value(false, char('.'))))(input)
+    }
+
+    fn algorithm(input: &str) -> IResult<&str, Algorithm> {
+        map_opt(many1(pixel), |pixels| pixels.try_into().ok())(input)
+    }
+
+    fn image(input: &str) -> IResult<&str, Image> {
+        map(map_opt(separated_list1(newline, many1(pixel)), Grid::from_rows),
+            |pixels| Image { pixels, background: false })(input)
+    }
+
+    pub(crate) fn trench_map(input: &str) -> IResult<&str, (Algorithm, Image)> {
+        separated_pair(algorithm, tag("\n\n"), image)(input)
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+
+    const TEST_INPUT: &str = "..#.#..#####.#.#.#.###.##.....###.##.#..###.####..#####..#....#..#..##..###..######.###...####..#..#####..##..#.#####...##.#.#..#.##..#.#......#.###.######.###.####...#.##.##..#..#..#####.....#.#....###..#.##......#.....#..#..#..##..#...##.######.####.####.#.#...#.......#..#.#.#...####.##.#......#..#...##.#.##..#...##.#.##..###.#......#.#.......#.#.#.####.###.##...#.....####.#..#..#.##.#....##..#.####....##...##..#...#......#.#.......#.......##..####..#...#.#.#...##..#.#..###..#####........#..####......#..#
+
+#..#.
+#....
+##..#
+..#..
+..###";
+
+    #[test]
+    fn test_parse() {
+        let (_, (algorithm, image)) = parse::trench_map(TEST_INPUT).unwrap();
+        assert!(!algorithm[0] && algorithm[34]);
+        assert_eq!(image.lit(), Some(10));
+        assert_eq!(image.to_string(), "#..#.\n#....\n##..#\n..#..\n..###\n");
+        assert!(parse::trench_map("#.#\n\n#.").is_err());
+    }
+
+    #[test]
+    fn test_enhance() {
+        let (_, (algorithm, image)) = parse::trench_map(TEST_INPUT).unwrap();
+        let once = image.enhance(&algorithm);
+        assert_eq!(once.to_string(), "\
+.##.##.
+#..#.#.
+##.#..#
+####..#
+.#..##.
+..##..#
+...#.#.
+");
+        assert_eq!(lit_after(&algorithm, &image, 2), 35);
+        assert_eq!(lit_after(&algorithm, &image, 50), 3351);
+    }
+
+    #[test]
+    fn test_flipping_background() {
+        let (_, (mut algorithm, image)) = parse::trench_map(TEST_INPUT).unwrap();
+        algorithm[0] = true;
+        algorithm[511] = false;
+
+        let mut images = passes(&algorithm, &image);
+        assert_eq!(images.next().unwrap().lit(), Some(10));
+        let odd = images.next().unwrap();
+        assert_eq!(odd.lit(), None);
+        assert!(odd.get(-5, 3));
+        assert!(images.next().unwrap().lit().is_some());
+    }
+
+    #[test]
+    fn test_frames() {
+        let (_, input) = parse::trench_map(TEST_INPUT).unwrap();
+        let frames = Day20::frames(&input, 3);
+        assert_eq!(frames.len(), 3);
+        assert!(frames[2].starts_with("pass 2, 35 lit\n"));
+    }
+}
@@ -0,0 +1,238 @@
+use std::collections::HashMap;
+
+use crate::Solution;
+use crate::util::{parse_all, ParseError};
+
+/// Every turn is three rolls of the die, added up.
+const ROLLS_PER_TURN: u32 = 3;
+
+/// The rules of a game. Built with [`Game::new`], so the board, target score and die are never zero.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub struct Game {
+    /// Spaces on the circular track, numbered from 1.
+    board_size: u32,
+    winning_score: u32,
+    die_sides: u32,
+}
+
+impl Game {
+    pub const PRACTICE: Game = Game { board_size: 10, winning_score: 1000, die_sides: 100 };
+    pub const DIRAC: Game = Game { board_size: 10, winning_score: 21, die_sides: 3 };
+
+    /// A game with the given rules, or `None` if any of them is zero.
+    pub fn new(board_size: u32, winning_score: u32, die_sides: u32) -> Option<Game> {
+        if board_size == 0 || winning_score == 0 || die_sides == 0 {
+            return None;
+        }
+        Some(Game { board_size, winning_score, die_sides })
+    }
+
+    /// Whether both players start on a space of the board.
+    fn on_board(&self, start: [u32; 2]) -> bool {
+        start.iter().all(|pos| (1..=self.board_size).contains(pos))
+    }
+
+    fn advance(&self, position: u32, by: u32) -> u32 {
+        (position - 1 + by) % self.board_size + 1
+    }
+
+    /// Plays with a die that rolls 1, 2, 3 and so on, wrapping round after its highest side, and returns the losing
+    /// player's score multiplied by how many times the die was rolled, or `None` if a player starts off the board.
+    pub fn play_deterministic(&self, start: [u32; 2]) -> Option<u64> {
+        if !self.on_board(start) {
+            return None;
+        }
+        let mut positions = start;
+        let mut scores = [0; 2];
+        let mut rolls = 0;
+        for player in [0, 1].into_iter().cycle() {
+            let total: u32 = (0..ROLLS_PER_TURN).map(|i| (rolls + i) % self.die_sides + 1).sum();
+            rolls += ROLLS_PER_TURN;
+            positions[player] = self.advance(positions[player], total);
+            scores[player] += positions[player];
+            if scores[player] >= self.winning_score {
+                return Some(scores[1 - player] as u64 * rolls as u64);
+            }
+        }
+        unreachable!()
+    }
+
+    /// How many ways each total of a turn's rolls can come up, when every roll splits the universe once per side.
+    fn roll_totals(&self) -> Vec<(u32, u64)> {
+        let mut totals = HashMap::from([(0, 1)]);
+        for _ in 0..ROLLS_PER_TURN {
+            let mut next = HashMap::new();
+            for (total, ways) in totals {
+                for side in 1..=self.die_sides {
+                    *next.entry(total + side).or_insert(0) += ways;
+                }
+            }
+            totals = next;
+        }
+        let mut totals: Vec<(u32, u64)> = totals.into_iter().collect();
+        totals.sort_unstable();
+        totals
+    }
+
+    /// Plays every possible game with the Dirac die and counts the universes in which each player wins, or `None` if a
+    /// player starts off the board.
+    pub fn play_quantum(&self, start: [u32; 2]) -> Option<[u64; 2]> {
+        if !self.on_board(start) {
+            return None;
+        }
+        let state = State { positions: start, scores: [0; 2], turn: 0 };
+        Some(wins(self, &self.roll_totals(), state, &mut HashMap::new()))
+    }
+}
+
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Hash)]
+struct State {
+    positions: [u32; 2],
+    scores: [u32; 2],
+    turn: usize,
+}
+
+fn wins(game: &Game, totals: &[(u32, u64)], state: State, memo: &mut HashMap<State, [u64; 2]>) -> [u64; 2] {
+    if let Some(&known) = memo.get(&state) {
+        return known;
+    }
+
+    let player = state.turn;
+    let mut result = [0; 2];
+    for &(total, ways) in totals {
+        let mut next = state;
+        next.positions[player] = game.advance(state.positions[player], total);
+        next.scores[player] += next.positions[player];
+        if next.scores[player] >= game.winning_score {
+            result[player] += ways;
+        } else {
+            next.turn = 1 - player;
+            let [a, b] = wins(game, totals, next, memo);
+            result[0] += a * ways;
+            result[1] += b * ways;
+        }
+    }
+    memo.insert(state, result);
+    result
+}
+
+pub struct Day21;
+
+impl Solution for Day21 {
+    const DAY: u32 = 21;
+
+    type Input = [u32; 2];
+    type Answer1 = u64;
+    type Answer2 = u64;
+
+    fn parse(input: &str) -> Result<[u32; 2], ParseError> {
+        parse_all(Self::DAY, input, parse::starting_positions)
+    }
+
+    fn part1(start: &[u32; 2]) -> u64 {
+        Game::PRACTICE.play_deterministic(*start).expect("starting positions are checked when parsed")
+    }
+
+    fn part2(start: &[u32; 2]) -> u64 {
+        let [a, b] = Game::DIRAC.play_quantum(*start).expect("starting positions are checked when parsed");
+        a.max(b)
+    }
+}
+
+mod parse {
+    use nom::bytes::complete::tag;
+    use nom::character::complete::{char, u32 as num};
+    use nom::combinator::verify;
+    use nom::IResult;
+    use nom::sequence::{delimited, preceded, separated_pair};
+
+    use super::*;
+
+    /// A starting space on the board both built-in games are played on, numbered from 1.
+    fn player<'a>(id: char) -> impl FnMut(&'a str) -> IResult<&'a str, u32> {
+        let space = verify(num, |pos| (1..=Game::DIRAC.board_size).contains(pos));
+        preceded(delimited(tag("Player "), char(id), tag(" starting position: ")), space)
+    }
+
+    pub(crate) fn starting_positions(input: &str) -> IResult<&str, [u32; 2]> {
+        let (input, (a, b)) = separated_pair(player('1'), char('\n'), player('2'))(input)?;
+        Ok((input, [a, b]))
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+
+    const TEST_INPUT: &str = "Player 1 starting position: 4\nPlayer 2 starting position: 8";
+
+    #[test]
+    fn test_parse() {
+        assert_eq!(parse::starting_positions(TEST_INPUT).unwrap().1, [4, 8]);
+        assert!(parse::starting_positions("Player 1 starting position: 0\nPlayer 2 starting position: 8").is_err());
+        assert!(parse::starting_positions("Player 1 starting position: 10\nPlayer 2 starting position: 10").is_ok());
+        let err = Day21::parse("Player 1 starting position: 4\nPlayer 2 starting position: 11").unwrap_err();
+        assert_eq!((err.line, err.column), (2, 29));
+    }
+
+    #[test]
+    fn test_off_board() {
+        assert_eq!(Game::PRACTICE.play_deterministic([11, 8]), None);
+        assert_eq!(Game::DIRAC.play_quantum([4, 0]), None);
+        let small = Game::new(4, 5, 2).unwrap();
+        assert_eq!(small.play_quantum([1, 5]), None);
+        assert!(small.play_quantum([1, 4]).is_some());
+    }
+
+    #[test]
+    fn test_deterministic() {
+        assert_eq!(Game::PRACTICE.play_deterministic([4, 8]), Some(739785));
+    }
+
+    #[test]
+    fn test_roll_totals() {
+        assert_eq!(Game::DIRAC.roll_totals(), [(3, 1), (4, 3), (5, 6), (6, 7), (7, 6), (8, 3), (9, 1)]);
+        let coin = Game::new(10, 21, 2).unwrap();
+        assert_eq!(coin.roll_totals(), [(3, 1), (4, 3), (5, 3), (6, 1)]);
+    }
+
+    #[test]
+    fn test_quantum() {
+        assert_eq!(Game::DIRAC.play_quantum([4, 8]), Some([444356092776315, 341960390180808]));
+    }
+
+    #[test]
+    fn test_other_games() {
+        // The first player reaches 3 on their first turn, whatever they roll
+        let quick = Game::new(10, 3, 3).unwrap();
+        assert_eq!(quick.play_quantum([1, 1]), Some([27, 0]));
+
+        let small = Game::new(4, 5, 2).unwrap();
+        let [a, b] = small.play_quantum([1, 2]).unwrap();
+        assert_eq!(a + b, brute_force(&small, [1, 2], [0, 0], 0));
+    }
+
+    #[test]
+    fn test_new() {
+        assert_eq!(Game::new(10, 21, 3), Some(Game::DIRAC));
+        assert_eq!(Game::new(0, 21, 3), None);
+        assert_eq!(Game::new(10, 0, 3), None);
+        assert_eq!(Game::new(10, 21, 0), None);
+    }
+
+    /// Counts finished games by trying every roll, without the memo.
+    fn brute_force(game: &Game, positions: [u32; 2], scores: [u32; 2], turn: usize) -> u64 {
+        let mut games = 0;
+        for (total, ways) in game.roll_totals() {
+            let (mut positions, mut scores) = (positions, scores);
+            positions[turn] = game.advance(positions[turn], total);
+            scores[turn] += positions[turn];
+            games += ways * if scores[turn] >= game.winning_score {
+                1
+            } else {
+                brute_force(game, positions, scores, 1 - turn)
+            };
+        }
+        games
+    }
+}
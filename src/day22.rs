@@ -0,0 +1,197 @@
+use std::collections::HashMap;
+
+use crate::Solution;
+use crate::util::{parse_all, ParseError};
+
+/// A box of cubes, from `min` to `max` inclusive along each axis.
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Hash)]
+pub struct Cuboid {
+    pub min: [i64; 3],
+    pub max: [i64; 3],
+}
+
+impl Cuboid {
+    /// The cuboid spanning `min..=max`, or `None` if it would be empty along some axis.
+    pub fn new(min: [i64; 3], max: [i64; 3]) -> Option<Cuboid> {
+        if (0..3).all(|axis| min[axis] <= max[axis]) { Some(Cuboid { min, max }) } else { None }
+    }
+
+    /// The region around the origin that the reactor initialises first.
+    pub fn initialisation_region() -> Cuboid {
+        Cuboid { min: [-50; 3], max: [50; 3] }
+    }
+
+    pub fn intersect(&self, other: &Cuboid) -> Option<Cuboid> {
+        let min = [0, 1, 2].map(|axis| self.min[axis].max(other.min[axis]));
+        let max = [0, 1, 2].map(|axis| self.max[axis].min(other.max[axis]));
+        Cuboid::new(min, max)
+    }
+
+    /// How many cubes the cuboid holds.
+    pub fn volume(&self) -> i64 {
+        (0..3).map(|axis| self.max[axis] - self.min[axis] + 1).product()
+    }
+
+    pub fn contains(&self, cube: [i64; 3]) -> bool {
+        (0..3).all(|axis| (self.min[axis]..=self.max[axis]).contains(&cube[axis]))
+    }
+}
+
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub struct Step {
+    pub on: bool,
+    pub cuboid: Cuboid,
+}
+
+/// Counts the cubes left on after every step, with inclusion–exclusion: each cuboid carries a sign, and every new
+/// step cancels out its overlap with those before it by adding their intersection with the opposite sign.
+pub fn lit_cubes(steps: &[Step]) -> i64 {
+    let mut signed: HashMap<Cuboid, i64> = HashMap::new();
+    for step in steps {
+        let mut changes: HashMap<Cuboid, i64> = HashMap::new();
+        for (cuboid, &sign) in &signed {
+            if let Some(overlap) = cuboid.intersect(&step.cuboid) {
+                *changes.entry(overlap).or_insert(0) -= sign;
+            }
+        }
+        if step.on {
+            *changes.entry(step.cuboid).or_insert(0) += 1;
+        }
+        for (cuboid, change) in changes {
+            let sign = signed.entry(cuboid).or_insert(0);
+            *sign += change;
+            if *sign == 0 {
+                signed.remove(&cuboid);
+            }
+        }
+    }
+    signed.iter().map(|(cuboid, sign)| cuboid.volume() * sign).sum()
+}
+
+/// Like [`lit_cubes`], but only counting cubes inside `region`.
+pub fn lit_cubes_within(steps: &[Step], region: &Cuboid) -> i64 {
+    let clipped: Vec<Step> = steps.iter()
+        .filter_map(|step| Some(Step { on: step.on, cuboid: step.cuboid.intersect(region)? }))
+        .collect();
+    lit_cubes(&clipped)
+}
+
+pub struct Day22;
+
+impl Solution for Day22 {
+    const DAY: u32 = 22;
+
+    type Input = Vec<Step>;
+    type Answer1 = i64;
+    type Answer2 = i64;
+
+    fn parse(input: &str) -> Result<Vec<Step>, ParseError> {
+        parse_all(Self::DAY, input, parse::reboot_steps)
+    }
+
+    fn part1(steps: &Vec<Step>) -> i64 {
+        lit_cubes_within(steps, &Cuboid::initialisation_region())
+    }
+
+    fn part2(steps: &Vec<Step>) -> i64 {
+        lit_cubes(steps)
+    }
+}
+
+mod parse {
+    use nom::branch::alt;
+    use nom::bytes::complete::tag;
+    use nom::character::complete::{char, i64 as num, newline};
+    use nom::combinator::{map, map_opt, value};
+    use nom::IResult;
+    use nom::sequence::{preceded, separated_pair, terminated, tuple};
+
+    use crate::util::lines1;
+
+    use super::*;
+
+    fn range(axis: &'static str) -> impl FnMut(&str) -> IResult<&str, (i64, i64)> {
+        move |input| preceded(tag(axis), separated_pair(num, tag(".."), num))(input)
+    }
+
+    fn cuboid(input: &str) -> IResult<&str, Cuboid> {
+        map_opt(tuple((terminated(range("x="), char(',')), terminated(range("y="), char(',')), range("z="))),
+                |((x0, x1), (y0, y1), (z0, z1))| Cuboid::new([x0, y0, z0], [x1, y1, z1]))(input)
+    }
+
+    fn step(input: &str) -> IResult<&str, Step> {
+        map(separated_pair(alt((value(true, tag("on")), value(false, tag("off")))), tag(" "), cuboid),
+            |(on, cuboid)| Step { on, cuboid })(input)
+    }
+
+    pub(crate) fn reboot_steps(input: &str) -> IResult<&str, Vec<Step>> {
+        lines1(newline, step)(input)
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+
+    const TEST_INPUT: &str = "on x=10..12,y=10..12,z=10..12
+on x=11..13,y=11..13,z=11..13
+off x=9..11,y=9..11,z=9..11
+on x=10..10,y=10..10,z=10..10";
+
+    /// Turns cubes on and off one at a time, for checking against.
+    fn brute_force(steps: &[Step], region: &Cuboid) -> i64 {
+        let mut count = 0;
+        for x in region.min[0]..=region.max[0] {
+            for y in region.min[1]..=region.max[1] {
+                for z in region.min[2]..=region.max[2] {
+                    let last = steps.iter().rev().find(|step| step.cuboid.contains([x, y, z]));
+                    count += last.is_some_and(|step| step.on) as i64;
+                }
+            }
+        }
+        count
+    }
+
+    #[test]
+    fn test_parse() {
+        let (_, steps) = parse::reboot_steps(TEST_INPUT).unwrap();
+        assert_eq!(steps.len(), 4);
+        assert_eq!(steps[2], Step { on: false, cuboid: Cuboid { min: [9, 9, 9], max: [11, 11, 11] } });
+        assert!(parse::reboot_steps("on x=3..1,y=0..0,z=0..0").is_err());
+    }
+
+    #[test]
+    fn test_cuboid() {
+        let a = Cuboid::new([0, 0, 0], [3, 3, 3]).unwrap();
+        let b = Cuboid::new([2, -5, 1], [9, 1, 1]).unwrap();
+        assert_eq!(a.volume(), 64);
+        assert_eq!(a.intersect(&b), Cuboid::new([2, 0, 1], [3, 1, 1]));
+        assert_eq!(a.intersect(&Cuboid::new([4, 0, 0], [5, 5, 5]).unwrap()), None);
+        assert!(Cuboid::new([1, 0, 0], [0, 0, 0]).is_none());
+    }
+
+    #[test]
+    fn test_lit_cubes() {
+        let (_, steps) = parse::reboot_steps(TEST_INPUT).unwrap();
+        assert_eq!(lit_cubes(&steps), 39);
+        assert_eq!(lit_cubes_within(&steps, &Cuboid::initialisation_region()), 39);
+        assert_eq!(lit_cubes_within(&steps, &Cuboid::new([0, 0, 0], [10, 10, 10]).unwrap()), 1);
+    }
+
+    #[test]
+    fn test_against_brute_force() {
+        let input = "on x=-5..3,y=-2..6,z=0..4
+off x=-1..8,y=1..2,z=-3..3
+on x=2..7,y=-6..0,z=2..9
+on x=-8..-2,y=-8..8,z=1..1
+off x=0..0,y=-9..9,z=-9..9
+on x=-3..4,y=-3..4,z=-3..4
+off x=-2..-1,y=0..3,z=2..5";
+        let (_, steps) = parse::reboot_steps(input).unwrap();
+        let everywhere = Cuboid::new([-10; 3], [10; 3]).unwrap();
+        assert_eq!(lit_cubes(&steps), brute_force(&steps, &everywhere));
+
+        let corner = Cuboid::new([-10; 3], [0, 2, 3]).unwrap();
+        assert_eq!(lit_cubes_within(&steps, &corner), brute_force(&steps, &corner));
+    }
+}
@@ -0,0 +1,290 @@
+use std::cmp::Reverse;
+use std::collections::{BinaryHeap, HashMap};
+use std::fmt::{Display, Formatter};
+
+use crate::Solution;
+use crate::util::{parse_all, ParseError};
+
+const HALLWAY: usize = 11;
+const ROOMS: usize = 4;
+const MAX_DEPTH: usize = 4;
+/// The hallway space outside each room, where nobody may stop.
+const DOORS: [usize; ROOMS] = [2, 4, 6, 8];
+const ENERGY: [u32; ROOMS] = [1, 10, 100, 1000];
+const EMPTY: u8 = 0;
+
+/// The two rows folded away in the diagram, that unfolding slots in under the top one.
+const FOLDED: [[u8; ROOMS]; 2] = [[4, 3, 2, 1], [4, 2, 1, 3]];
+
+/// Where every amphipod is. Each cell is [`EMPTY`] or an amphipod type from 1 (Amber) to 4 (Desert), which also
+/// names the room it wants to end up in; rooms are listed top down, and only the first `depth` spaces are used.
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Hash, PartialOrd, Ord)]
+pub struct Burrow {
+    hallway: [u8; HALLWAY],
+    rooms: [[u8; MAX_DEPTH]; ROOMS],
+    depth: usize,
+}
+
+fn letter(amphipod: u8) -> char {
+    if amphipod == EMPTY { '.' } else { (b'A' + amphipod - 1) as char }
+}
+
+impl Burrow {
+    fn room(&self, r: usize) -> &[u8] {
+        &self.rooms[r][..self.depth]
+    }
+
+    /// Whether a room holds only amphipods that belong there, so others can move in.
+    fn is_clean(&self, r: usize) -> bool {
+        self.room(r).iter().all(|&a| a == EMPTY || a as usize == r + 1)
+    }
+
+    pub fn is_organised(&self) -> bool {
+        self.hallway.iter().all(|&a| a == EMPTY)
+            && (0..ROOMS).all(|r| self.room(r).iter().all(|&a| a as usize == r + 1))
+    }
+
+    /// Whether every hallway space strictly after `from` up to and including `to` is empty.
+    fn hallway_clear(&self, from: usize, to: usize) -> bool {
+        let path = if from < to { from + 1..=to } else { to..=from - 1 };
+        self.hallway[path].iter().all(|&a| a == EMPTY)
+    }
+
+    /// Every burrow one legal move away, with the energy it costs: either the top amphipod in a room that still holds
+    /// strangers steps out to a hallway space that isn't a doorway, or an amphipod in the hallway walks all the way
+    /// into its own room, once that room is clean.
+    fn moves(&self) -> Vec<(u32, Burrow)> {
+        let mut moves = Vec::new();
+
+        for (h, &amphipod) in self.hallway.iter().enumerate() {
+            if amphipod == EMPTY { continue; }
+            let r = amphipod as usize - 1;
+            if !self.is_clean(r) || !self.hallway_clear(h, DOORS[r]) { continue; }
+            // A room can only be full of its own amphipods with one still outside if there are too many of them
+            let Some(slot) = self.room(r).iter().rposition(|&a| a == EMPTY) else { continue };
+            let mut next = *self;
+            next.hallway[h] = EMPTY;
+            next.rooms[r][slot] = amphipod;
+            moves.push(((h.abs_diff(DOORS[r]) + slot + 1) as u32 * ENERGY[r], next));
+        }
+
+        for (r, &door) in DOORS.iter().enumerate() {
+            if self.is_clean(r) { continue; }
+            let slot = self.room(r).iter().position(|&a| a != EMPTY).unwrap();
+            let amphipod = self.rooms[r][slot];
+
+            for h in (0..HALLWAY).filter(|h| !DOORS.contains(h)) {
+                if !self.hallway_clear(door, h) { continue; }
+
+                let mut next = *self;
+                next.rooms[r][slot] = EMPTY;
+                next.hallway[h] = amphipod;
+                moves.push(((slot + 1 + h.abs_diff(door)) as u32 * ENERGY[amphipod as usize - 1], next));
+            }
+        }
+        moves
+    }
+
+    /// The burrow with the two folded-away rows of the full diagram inserted below the top row. A burrow that's
+    /// already at full depth is left as it is.
+    pub fn unfold(&self) -> Burrow {
+        if self.depth == MAX_DEPTH {
+            return *self;
+        }
+        let mut unfolded = *self;
+        unfolded.depth += FOLDED.len();
+        for (r, room) in unfolded.rooms.iter_mut().enumerate() {
+            let below = self.room(r)[1..].iter().copied();
+            let column = [self.rooms[r][0], FOLDED[0][r], FOLDED[1][r]].into_iter().chain(below);
+            for (space, amphipod) in room.iter_mut().zip(column) {
+                *space = amphipod;
+            }
+        }
+        unfolded
+    }
+
+    /// The least energy needed to organise every amphipod into its own room, or `None` if they can't be.
+    pub fn organise(&self) -> Option<u32> {
+        let mut best = HashMap::from([(*self, 0)]);
+        let mut queue = BinaryHeap::from([Reverse((0, *self))]);
+        while let Some(Reverse((energy, burrow))) = queue.pop() {
+            if burrow.is_organised() {
+                return Some(energy);
+            }
+            if energy > best[&burrow] { continue; }
+
+            for (cost, next) in burrow.moves() {
+                let next_energy = energy + cost;
+                if best.get(&next).is_none_or(|&e| next_energy < e) {
+                    best.insert(next, next_energy);
+                    queue.push(Reverse((next_energy, next)));
+                }
+            }
+        }
+        None
+    }
+}
+
+/// Draws the burrow as in the puzzle's diagrams.
+impl Display for Burrow {
+    fn fmt(&self, f: &mut Formatter<'_>) -> std::fmt::Result {
+        writeln!(f, "#############")?;
+        writeln!(f, "#{}#", self.hallway.iter().map(|&a| letter(a)).collect::<String>())?;
+        for slot in 0..self.depth {
+            let row: Vec<String> = self.rooms.iter().map(|room| letter(room[slot]).to_string()).collect();
+            let edge = if slot == 0 { "##" } else { "  " };
+            writeln!(f, "{}#{}#{}", edge, row.join("#"), edge.trim())?;
+        }
+        writeln!(f, "  #########")
+    }
+}
+
+pub struct Day23;
+
+impl Solution for Day23 {
+    const DAY: u32 = 23;
+
+    type Input = Burrow;
+    type Answer1 = u32;
+    type Answer2 = u32;
+
+    fn parse(input: &str) -> Result<Burrow, ParseError> {
+        parse_all(Self::DAY, input, parse::burrow)
+    }
+
+    fn part1(burrow: &Burrow) -> u32 {
+        burrow.organise().expect("amphipods can't be organised")
+    }
+
+    fn part2(burrow: &Burrow) -> u32 {
+        burrow.unfold().organise().expect("amphipods can't be organised")
+    }
+}
+
+mod parse {
+    use nom::branch::alt;
+    use nom::bytes::complete::tag;
+    use nom::character::complete::{char, newline, one_of};
+    use nom::combinator::{map, map_opt, value, verify};
+    use nom::IResult;
+    use nom::multi::{count, many_m_n};
+    use nom::sequence::{delimited, pair, preceded, terminated};
+
+    use super::*;
+
+    fn space(input: &str) -> IResult<&str, u8> {
+        alt((value(EMPTY, char('.')), map(one_of("ABCD"), |ch| ch as u8 - b'A' + 1)))(input)
+    }
+
+    fn hallway(input: &str) -> IResult<&str, [u8; HALLWAY]> {
+        map_opt(delimited(char('#'), count(space, HALLWAY), char('#')), |spaces| spaces.try_into().ok())(input)
+    }
+
+    fn row(input: &str) -> IResult<&str, [u8; ROOMS]> {
+        let rooms = map_opt(count(terminated(space, char('#')), ROOMS), |spaces| spaces.try_into().ok());
+        terminated(preceded(alt((tag("###"), tag("  #"))), rooms), many_m_n(0, 2, char('#')))(input)
+    }
+
+    pub(crate) fn burrow(input: &str) -> IResult<&str, Burrow> {
+        let (input, hallway) = delimited(tag("#############\n"), hallway, newline)(input)?;
+        // Either the folded diagram, or the full one
+        let rows = verify(many_m_n(2, MAX_DEPTH, terminated(row, newline)), |rows: &Vec<_>| rows.len() % 2 == 0);
+        let (input, (rows, _)) = pair(rows, tag("  #########"))(input)?;
+
+        let mut rooms = [[EMPTY; MAX_DEPTH]; ROOMS];
+        for (slot, row) in rows.iter().enumerate() {
+            for (room, &amphipod) in rooms.iter_mut().zip(row) {
+                room[slot] = amphipod;
+            }
+        }
+        Ok((input, Burrow { hallway, rooms, depth: rows.len() }))
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+
+    const TEST_INPUT: &str = "#############
+#...........#
+###B#C#B#D###
+  #A#D#C#A#
+  #########";
+
+    #[test]
+    fn test_parse() {
+        let (_, burrow) = parse::burrow(TEST_INPUT).unwrap();
+        assert_eq!(burrow.depth, 2);
+        assert_eq!(burrow.room(0), [2, 1]);
+        assert_eq!(burrow.room(3), [4, 1]);
+        assert_eq!(burrow.to_string(), format!("{}\n", TEST_INPUT));
+    }
+
+    #[test]
+    fn test_unfold() {
+        let (_, burrow) = parse::burrow(TEST_INPUT).unwrap();
+        let unfolded = burrow.unfold();
+        assert_eq!(unfolded.to_string(), "#############
+#...........#
+###B#C#B#D###
+  #D#C#B#A#
+  #D#B#A#C#
+  #A#D#C#A#
+  #########
+");
+        assert_eq!(parse::burrow(unfolded.to_string().trim_end()).unwrap().1, unfolded);
+    }
+
+    #[test]
+    fn test_parse_depths() {
+        let one_row = "#############\n#...........#\n###B#C#B#D###\n  #########";
+        assert!(Day23::parse(one_row).is_err());
+        let three_rows = "#############\n#...........#\n###B#C#B#D###\n  #D#C#B#A#\n  #A#D#C#A#\n  #########";
+        assert!(Day23::parse(three_rows).is_err());
+    }
+
+    #[test]
+    fn test_already_unfolded() {
+        let (_, burrow) = parse::burrow(TEST_INPUT).unwrap();
+        let unfolded = burrow.unfold();
+        assert_eq!(unfolded.unfold(), unfolded);
+        assert_eq!(Day23::part2(&unfolded), 44169);
+    }
+
+    #[test]
+    fn test_too_many_amphipods() {
+        let (_, burrow) = parse::burrow("#############
+#A..........#
+###A#B#C#D###
+  #A#B#C#D#
+  #########").unwrap();
+        assert_eq!(burrow.organise(), None);
+    }
+
+    #[test]
+    fn test_moves() {
+        let (_, burrow) = parse::burrow("#############
+#...B.A.....#
+###.#.#C#D###
+  #A#B#C#D#
+  #########").unwrap();
+        // The B is in the A's way, and every room is clean so nobody needs to leave one
+        assert_eq!(burrow.moves().len(), 1);
+        let (cost, next) = burrow.moves()[0];
+        assert_eq!(cost, 20);
+        assert_eq!(next.room(1), [2, 2]);
+        assert_eq!(burrow.organise(), Some(24));
+    }
+
+    #[test]
+    fn test_organise() {
+        let (_, burrow) = parse::burrow(TEST_INPUT).unwrap();
+        assert_eq!(burrow.organise(), Some(12521));
+    }
+
+    #[test]
+    fn test_organise_unfolded() {
+        let (_, burrow) = parse::burrow(TEST_INPUT).unwrap();
+        assert_eq!(burrow.unfold().organise(), Some(44169));
+    }
+}
@@ -0,0 +1,415 @@
+use std::fmt::{Display, Formatter};
+
+use crate::Solution;
+use crate::util::{parse_all, ParseError};
+
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum Register {
+    W,
+    X,
+    Y,
+    Z,
+}
+
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum Operand {
+    Register(Register),
+    Number(i64),
+}
+
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum Instruction {
+    Inp(Register),
+    Add(Register, Operand),
+    Mul(Register, Operand),
+    Div(Register, Operand),
+    Mod(Register, Operand),
+    Eql(Register, Operand),
+}
+
+impl Display for Register {
+    fn fmt(&self, f: &mut Formatter<'_>) -> std::fmt::Result {
+        let name = match self {
+            Register::W => "w",
+            Register::X => "x",
+            Register::Y => "y",
+            Register::Z => "z",
+        };
+        write!(f, "{}", name)
+    }
+}
+
+impl Display for Operand {
+    fn fmt(&self, f: &mut Formatter<'_>) -> std::fmt::Result {
+        match self {
+            Operand::Register(r) => write!(f, "{}", r),
+            Operand::Number(n) => write!(f, "{}", n),
+        }
+    }
+}
+
+impl Display for Instruction {
+    fn fmt(&self, f: &mut Formatter<'_>) -> std::fmt::Result {
+        let (name, a, b) = match *self {
+            Instruction::Inp(a) => return write!(f, "inp {}", a),
+            Instruction::Add(a, b) => ("add", a, b),
+            Instruction::Mul(a, b) => ("mul", a, b),
+            Instruction::Div(a, b) => ("div", a, b),
+            Instruction::Mod(a, b) => ("mod", a, b),
+            Instruction::Eql(a, b) => ("eql", a, b),
+        };
+        write!(f, "{} {} {}", name, a, b)
+    }
+}
+
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum AluError {
+    /// The instruction at this index wanted more input than there was.
+    InputExhausted(usize),
+    DivideByZero(usize),
+    /// The result of the instruction at this index doesn't fit in a register.
+    Overflow(usize),
+    /// `mod` needs a non-negative number and a positive divisor.
+    BadModulo(usize),
+}
+
+impl Display for AluError {
+    fn fmt(&self, f: &mut Formatter<'_>) -> std::fmt::Result {
+        match self {
+            AluError::InputExhausted(i) => write!(f, "instruction {} ran out of input", i),
+            AluError::DivideByZero(i) => write!(f, "instruction {} divided by zero", i),
+            AluError::Overflow(i) => write!(f, "instruction {} overflowed", i),
+            AluError::BadModulo(i) => write!(f, "instruction {} took a modulo with a negative number or divisor", i),
+        }
+    }
+}
+
+impl std::error::Error for AluError {}
+
+/// The four registers, all starting at zero.
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq)]
+pub struct Alu {
+    registers: [i64; 4],
+}
+
+impl Alu {
+    pub fn get(&self, r: Register) -> i64 {
+        self.registers[r as usize]
+    }
+
+    fn value(&self, operand: Operand) -> i64 {
+        match operand {
+            Operand::Register(r) => self.get(r),
+            Operand::Number(n) => n,
+        }
+    }
+
+    /// Runs `program` to the end, reading `inp` values from `input` in order.
+    pub fn run(program: &[Instruction], input: impl IntoIterator<Item=i64>) -> Result<Alu, AluError> {
+        let mut alu = Alu::default();
+        let mut input = input.into_iter();
+        for (i, &instruction) in program.iter().enumerate() {
+            let (a, result) = match instruction {
+                Instruction::Inp(a) => (a, input.next().ok_or(AluError::InputExhausted(i))?),
+                Instruction::Add(a, b) => (a, alu.get(a).checked_add(alu.value(b)).ok_or(AluError::Overflow(i))?),
+                Instruction::Mul(a, b) => (a, alu.get(a).checked_mul(alu.value(b)).ok_or(AluError::Overflow(i))?),
+                Instruction::Div(a, b) => match alu.value(b) {
+                    0 => return Err(AluError::DivideByZero(i)),
+                    d => (a, alu.get(a).checked_div(d).ok_or(AluError::Overflow(i))?),
+                },
+                Instruction::Mod(a, b) => {
+                    let (n, d) = (alu.get(a), alu.value(b));
+                    if n < 0 || d <= 0 {
+                        return Err(AluError::BadModulo(i));
+                    }
+                    (a, n % d)
+                }
+                Instruction::Eql(a, b) => (a, (alu.get(a) == alu.value(b)) as i64),
+            };
+            alu.registers[a as usize] = result;
+        }
+        Ok(alu)
+    }
+}
+
+const BLOCK_LEN: usize = 18;
+
+/// One digit's worth of MONAD. Every block treats `z` as a stack of base-26 digits: a block that doesn't pop pushes
+/// `w + offset`, and one that pops only avoids pushing again if `w` equals the popped value plus `check`.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub struct Block {
+    pub pops: bool,
+    pub check: i64,
+    pub offset: i64,
+}
+
+impl Block {
+    fn instructions(&self) -> [Instruction; BLOCK_LEN] {
+        use Instruction::*;
+        use Operand::Number;
+        use Register::*;
+
+        let reg = Operand::Register;
+        [
+            Inp(W), Mul(X, Number(0)), Add(X, reg(Z)), Mod(X, Number(26)),
+            Div(Z, Number(if self.pops { 26 } else { 1 })), Add(X, Number(self.check)),
+            Eql(X, reg(W)), Eql(X, Number(0)),
+            Mul(Y, Number(0)), Add(Y, Number(25)), Mul(Y, reg(X)), Add(Y, Number(1)), Mul(Z, reg(Y)),
+            Mul(Y, Number(0)), Add(Y, reg(W)), Add(Y, Number(self.offset)), Mul(Y, reg(X)), Add(Z, reg(Y)),
+        ]
+    }
+
+    /// Reads a block's parameters back out of its instructions, if they follow the MONAD pattern exactly.
+    fn recognise(instructions: &[Instruction]) -> Option<Block> {
+        let pops = match instructions.get(4)? {
+            Instruction::Div(Register::Z, Operand::Number(1)) => false,
+            Instruction::Div(Register::Z, Operand::Number(26)) => true,
+            _ => return None
+        };
+        let Instruction::Add(Register::X, Operand::Number(check)) = *instructions.get(5)? else { return None };
+        let Instruction::Add(Register::Y, Operand::Number(offset)) = *instructions.get(15)? else { return None };
+
+        let block = Block { pops, check, offset };
+        if block.instructions() == instructions { Some(block) } else { None }
+    }
+}
+
+/// Splits a MONAD program into its blocks, or `None` if it isn't one.
+pub fn monad_blocks(program: &[Instruction]) -> Option<Vec<Block>> {
+    if program.is_empty() || !program.len().is_multiple_of(BLOCK_LEN) {
+        return None;
+    }
+    program.chunks(BLOCK_LEN).map(Block::recognise).collect()
+}
+
+/// The largest or smallest model number MONAD accepts, found without running it. Each popping block is paired with
+/// the pushing block whose value it pops, which ties the two digits together: `popper = pusher + offset + check`.
+/// Every pair is then set independently, pushing the larger digit up to 9 (or the smaller one down to 1).
+///
+/// This relies on every non-popping block having a `check` of at least 10, so that it always pushes whatever the
+/// digit; `None` if one doesn't, or if no digits satisfy a pair.
+pub fn model_number(blocks: &[Block], largest: bool) -> Option<u64> {
+    let mut digits = vec![0; blocks.len()];
+    let mut stack = Vec::new();
+    for (i, block) in blocks.iter().enumerate() {
+        if !block.pops {
+            if block.check < 10 {
+                return None;
+            }
+            stack.push((i, block.offset));
+            continue;
+        }
+        let (j, offset) = stack.pop()?;
+        let diff = offset + block.check;
+        if diff.abs() > 8 {
+            return None;
+        }
+        let pusher = if largest { 9.min(9 - diff) } else { 1.max(1 - diff) };
+        digits[j] = pusher;
+        digits[i] = pusher + diff;
+    }
+    if !stack.is_empty() {
+        return None;
+    }
+    Some(digits.iter().fold(0, |n, &d| n * 10 + d as u64))
+}
+
+pub fn digits(model: u64) -> Vec<i64> {
+    model.to_string().bytes().map(|b| (b - b'0') as i64).collect()
+}
+
+/// Whether MONAD, run on the ALU, accepts `model`.
+pub fn accepts(program: &[Instruction], model: u64) -> Result<bool, AluError> {
+    Ok(Alu::run(program, digits(model))?.get(Register::Z) == 0)
+}
+
+fn solve(program: &[Instruction], largest: bool) -> u64 {
+    let blocks = monad_blocks(program).expect("not a MONAD program");
+    let model = model_number(&blocks, largest).expect("MONAD accepts no model numbers");
+    debug_assert_eq!(accepts(program, model), Ok(true));
+    model
+}
+
+pub struct Day24;
+
+impl Solution for Day24 {
+    const DAY: u32 = 24;
+
+    type Input = Vec<Instruction>;
+    type Answer1 = u64;
+    type Answer2 = u64;
+
+    fn parse(input: &str) -> Result<Vec<Instruction>, ParseError> {
+        parse_all(Self::DAY, input, parse::program)
+    }
+
+    fn part1(program: &Vec<Instruction>) -> u64 {
+        solve(program, true)
+    }
+
+    fn part2(program: &Vec<Instruction>) -> u64 {
+        solve(program, false)
+    }
+}
+
+mod parse {
+    use nom::branch::alt;
+    use nom::bytes::complete::tag;
+    use nom::character::complete::{char, i64 as num, newline};
+    use nom::combinator::{map, value};
+    use nom::IResult;
+    use nom::sequence::{preceded, separated_pair};
+
+    use crate::util::lines1;
+
+    use super::*;
+
+    fn register(input: &str) -> IResult<&str, Register> {
+        alt((
+            value(Register::W, char('w')),
+            value(Register::X, char('x')),
+            value(Register::Y, char('y')),
+            value(Register::Z, char('z')),
+        ))(input)
+    }
+
+    fn operand(input: &str) -> IResult<&str, Operand> {
+        alt((map(register, Operand::Register), map(num, Operand::Number)))(input)
+    }
+
+    fn binary<'a>(name: &'static str, make: fn(Register, Operand) -> Instruction)
+                  -> impl FnMut(&'a str) -> IResult<&'a str, Instruction> {
+        map(preceded(tag(name), separated_pair(register, char(' '), operand)), move |(a, b)| make(a, b))
+    }
+
+    fn instruction(input: &str) -> IResult<&str, Instruction> {
+        alt((
+            map(preceded(tag("inp "), register), Instruction::Inp),
+            binary("add ", Instruction::Add),
+            binary("mul ", Instruction::Mul),
+            binary("div ", Instruction::Div),
+            binary("mod ", Instruction::Mod),
+            binary("eql ", Instruction::Eql),
+        ))(input)
+    }
+
+    pub(crate) fn program(input: &str) -> IResult<&str, Vec<Instruction>> {
+        lines1(newline, instruction)(input)
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+
+    const BINARY: &str = "inp w
+add z w
+mod z 2
+div w 2
+add y w
+mod y 2
+div w 2
+add x w
+mod x 2
+div w 2
+mod w 2";
+
+    fn program(text: &str) -> Vec<Instruction> {
+        parse::program(text).unwrap().1
+    }
+
+    /// A MONAD-shaped program with nested pushes and pops, whose answers were worked out by hand.
+    fn monad() -> Vec<Block> {
+        let push = |check, offset| Block { pops: false, check, offset };
+        let pop = |check, offset| Block { pops: true, check, offset };
+        vec![
+            push(12, 4), push(11, 11), push(13, 5), pop(-5, 2), push(10, 6), push(15, 7), pop(-1, 3),
+            pop(-10, 9), pop(-6, 1), push(14, 2), push(13, 12), pop(-14, 8), pop(0, 5), pop(-8, 10),
+        ]
+    }
+
+    #[test]
+    fn test_parse() {
+        let instructions = program(BINARY);
+        assert_eq!(instructions.len(), 11);
+        assert_eq!(instructions[0], Instruction::Inp(Register::W));
+        assert_eq!(instructions[2], Instruction::Mod(Register::Z, Operand::Number(2)));
+        assert_eq!(instructions.iter().map(|i| i.to_string()).collect::<Vec<_>>().join("\n"), BINARY);
+
+        let negative = program("add x -13");
+        assert_eq!(negative, [Instruction::Add(Register::X, Operand::Number(-13))]);
+        assert!(parse::program("sub x 1").is_err());
+    }
+
+    #[test]
+    fn test_run() {
+        let negate = program("inp x\nmul x -1");
+        assert_eq!(Alu::run(&negate, [7]).unwrap().get(Register::X), -7);
+
+        let is_triple = program("inp z\ninp x\nmul z 3\neql z x");
+        assert_eq!(Alu::run(&is_triple, [3, 9]).unwrap().get(Register::Z), 1);
+        assert_eq!(Alu::run(&is_triple, [3, 8]).unwrap().get(Register::Z), 0);
+
+        let bits = Alu::run(&program(BINARY), [11]).unwrap();
+        let regs = [Register::W, Register::X, Register::Y, Register::Z].map(|r| bits.get(r));
+        assert_eq!(regs, [1, 0, 1, 1]);
+    }
+
+    #[test]
+    fn test_run_errors() {
+        assert_eq!(Alu::run(&program("inp x\ninp y"), [1]), Err(AluError::InputExhausted(1)));
+        assert_eq!(Alu::run(&program("div x y"), []), Err(AluError::DivideByZero(0)));
+        assert_eq!(Alu::run(&program("add x -3\nmod x 2"), []), Err(AluError::BadModulo(1)));
+        assert_eq!(AluError::DivideByZero(4).to_string(), "instruction 4 divided by zero");
+    }
+
+    #[test]
+    fn test_run_overflow() {
+        let square_thrice = program("inp x\nmul x x\nmul x x\nmul x x");
+        assert_eq!(Alu::run(&square_thrice, [10]).unwrap().get(Register::X), 100_000_000);
+        assert_eq!(Alu::run(&square_thrice, [100_000]), Err(AluError::Overflow(2)));
+        assert_eq!(Alu::run(&program("inp x\nadd x 1"), [i64::MAX]), Err(AluError::Overflow(1)));
+        assert_eq!(Alu::run(&program("inp x\ndiv x -1"), [i64::MIN]), Err(AluError::Overflow(1)));
+        assert_eq!(Alu::run(&program("inp x\ndiv x -1"), [i64::MIN + 1]).unwrap().get(Register::X), i64::MAX);
+    }
+
+    #[test]
+    fn test_monad_blocks() {
+        let blocks = monad();
+        let instructions: Vec<Instruction> = blocks.iter().flat_map(Block::instructions).collect();
+        let text: Vec<String> = instructions.iter().map(|i| i.to_string()).collect();
+        assert_eq!(text[4..6], ["div z 1".to_owned(), "add x 12".to_owned()][..]);
+
+        let program = program(&text.join("\n"));
+        assert_eq!(monad_blocks(&program), Some(blocks));
+        assert_eq!(monad_blocks(&program[..20]), None);
+
+        let mut tampered = program.clone();
+        tampered[7] = Instruction::Eql(Register::X, Operand::Number(1));
+        assert_eq!(monad_blocks(&tampered), None);
+    }
+
+    #[test]
+    fn test_model_numbers() {
+        let blocks = monad();
+        let program: Vec<Instruction> = blocks.iter().flat_map(Block::instructions).collect();
+
+        let largest = model_number(&blocks, true).unwrap();
+        let smallest = model_number(&blocks, false).unwrap();
+        assert_eq!(largest, 94999395979795);
+        assert_eq!(smallest, 51115171613131);
+        assert_eq!(accepts(&program, largest), Ok(true));
+        assert_eq!(accepts(&program, smallest), Ok(true));
+        assert_eq!(accepts(&program, largest - 1), Ok(false));
+        assert_eq!(Day24::part1(&program), largest);
+    }
+
+    #[test]
+    fn test_impossible_pair() {
+        let blocks = [Block { pops: false, check: 10, offset: 9 }, Block { pops: true, check: 1, offset: 0 }];
+        assert_eq!(model_number(&blocks, true), None);
+        assert_eq!(model_number(&blocks[..1], true), None);
+        let unreliable_push = [Block { pops: false, check: 3, offset: 0 }, Block { pops: true, check: 0, offset: 0 }];
+        assert_eq!(model_number(&unreliable_push, true), None);
+    }
+}
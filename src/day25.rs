@@ -0,0 +1,206 @@
+use std::collections::HashSet;
+use std::fmt::{Display, Formatter};
+
+use crate::Solution;
+use crate::util::{self, Grid, parse_all, ParseError};
+use crate::util::grid::Pos;
+
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Hash)]
+pub enum Cell {
+    Empty,
+    East,
+    South,
+}
+
+impl Display for Cell {
+    fn fmt(&self, f: &mut Formatter<'_>) -> std::fmt::Result {
+        let ch = match self {
+            Cell::Empty => '.',
+            Cell::East => '>',
+            Cell::South => 'v',
+        };
+        write!(f, "{}", ch)
+    }
+}
+
+/// The two herds of sea cucumbers on a seafloor that wraps round at the edges.
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct Seafloor {
+    cells: Grid<Cell>,
+}
+
+impl Seafloor {
+    /// Moves every cucumber in `herd` whose next space was empty before any of them moved, returning how many did.
+    fn move_herd(&mut self, herd: Cell) -> usize {
+        let (width, height) = (self.cells.width(), self.cells.height());
+        let ahead = |(x, y): Pos| match herd {
+            Cell::East => ((x + 1) % width, y),
+            _ => (x, (y + 1) % height),
+        };
+        let movers: Vec<Pos> = self.cells.positions()
+            .filter(|&pos| self.cells[pos] == herd && self.cells[ahead(pos)] == Cell::Empty)
+            .collect();
+        for &pos in &movers {
+            self.cells[pos] = Cell::Empty;
+            self.cells[ahead(pos)] = herd;
+        }
+        movers.len()
+    }
+
+    /// Moves the east-facing herd, then the south-facing one, returning how many cucumbers moved in all.
+    pub fn step(&mut self) -> usize {
+        self.move_herd(Cell::East) + self.move_herd(Cell::South)
+    }
+
+    /// Steps until nothing moves, returning the number of that first step where nothing did, or `None` if the herds
+    /// come back round to a layout they've been in before and so will keep moving forever.
+    pub fn steps_until_stuck(&mut self) -> Option<usize> {
+        let mut seen = HashSet::from([self.cells.clone()]);
+        let mut steps = 1;
+        while self.step() > 0 {
+            if !seen.insert(self.cells.clone()) {
+                return None;
+            }
+            steps += 1;
+        }
+        Some(steps)
+    }
+
+    /// The seafloor as it is now and after each step, `limit` frames in all, stopping once nothing moves.
+    pub fn frames(&mut self, limit: usize) -> Vec<String> {
+        util::frames(self, limit, |seafloor| {
+            let moved = seafloor.step();
+            (moved, moved == 0)
+        }, |seafloor, step, moved| format!("step {}, {} moved\n{}", step, moved, seafloor))
+    }
+}
+
+impl Display for Seafloor {
+    fn fmt(&self, f: &mut Formatter<'_>) -> std::fmt::Result {
+        write!(f, "{}", self.cells)
+    }
+}
+
+pub struct Day25;
+
+impl Solution for Day25 {
+    const DAY: u32 = 25;
+
+    type Input = Seafloor;
+    type Answer1 = String;
+    type Answer2 = &'static str;
+
+    fn parse(input: &str) -> Result<Seafloor, ParseError> {
+        parse_all(Self::DAY, input, parse::seafloor)
+    }
+
+    fn part1(seafloor: &Seafloor) -> String {
+        let steps = seafloor.clone().steps_until_stuck();
+        steps.map_or("never, the herds keep moving forever".to_owned(), |n| n.to_string())
+    }
+
+    /// The last day only has the one puzzle.
+    fn part2(_seafloor: &Seafloor) -> &'static str {
+        "no puzzle"
+    }
+
+    fn frames(seafloor: &Seafloor, limit: usize) -> Vec<String> {
+        seafloor.clone().frames(limit)
+    }
+}
+
+mod parse {
+    use nom::branch::alt;
+    use nom::character::complete::{char, newline};
+    use nom::combinator::{map, map_opt, value};
+    use nom::IResult;
+    use nom::multi::{many1, separated_list1};
+
+    use super::*;
+
+    fn cell(input: &str) -> IResult<&str, Cell> {
+        alt((value(Cell::Empty, char('.')), value(Cell::East, char('>')), value(Cell::South, char('v'))))(input)
+    }
+
+    pub(crate) fn seafloor(input: &str) -> IResult<&str, Seafloor> {
+        map(map_opt(separated_list1(newline, many1(cell)), Grid::from_rows), |cells| Seafloor { cells })(input)
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+
+    const TEST_INPUT: &str = "v...>>.vv>
+.vv>>.vv..
+>>.>v>...v
+>>v>>.>.v.
+v>v.vv.v..
+>.>>..v...
+.vv..>.>v.
+v.v..>>v.v
+....v..v.>";
+
+    #[test]
+    fn test_parse() {
+        let (_, seafloor) = parse::seafloor(TEST_INPUT).unwrap();
+        assert_eq!((seafloor.cells.width(), seafloor.cells.height()), (10, 9));
+        assert_eq!(seafloor.to_string(), format!("{}\n", TEST_INPUT));
+        assert!(parse::seafloor("..>\n.v").is_err());
+    }
+
+    #[test]
+    fn test_step() {
+        let (_, mut seafloor) = parse::seafloor("...>>>>>...").unwrap();
+        assert_eq!(seafloor.step(), 1);
+        assert_eq!(seafloor.to_string(), "...>>>>.>..\n");
+        assert_eq!(seafloor.step(), 2);
+        assert_eq!(seafloor.to_string(), "...>>>.>.>.\n");
+
+        let (_, mut seafloor) = parse::seafloor("..........\n.>v....v..\n.......>..\n..........").unwrap();
+        seafloor.step();
+        assert_eq!(seafloor.to_string(), "..........\n.>........\n..v....v>.\n..........\n");
+    }
+
+    #[test]
+    fn test_wrap_around() {
+        let (_, mut seafloor) = parse::seafloor("...>...\n.......\n......>\nv.....>\n......>\n.......\n..vvv..").unwrap();
+        seafloor.step();
+        assert_eq!(seafloor.to_string(), "..vv>..\n.......\n>......\nv.....>\n>......\n.......\n....v..\n");
+    }
+
+    #[test]
+    fn test_steps_until_stuck() {
+        let (_, mut seafloor) = parse::seafloor(TEST_INPUT).unwrap();
+        let mut once = seafloor.clone();
+        once.step();
+        assert_eq!(once.to_string(), "....>.>v.>
+v.v>.>v.v.
+>v>>..>v..
+>>v>v>.>.v
+.>v.v...v.
+v>>.>vvv..
+..v...>>..
+vv...>>vv.
+>.v.v..v.v
+");
+        assert_eq!(seafloor.steps_until_stuck(), Some(58));
+    }
+
+    #[test]
+    fn test_never_stuck() {
+        let (_, mut seafloor) = parse::seafloor(">.").unwrap();
+        assert_eq!(seafloor.steps_until_stuck(), None);
+        let (_, seafloor) = parse::seafloor(">.\n.v").unwrap();
+        assert_eq!(Day25::part1(&seafloor), "never, the herds keep moving forever");
+        assert_eq!(Day25::part1(&parse::seafloor(">>").unwrap().1), "1");
+    }
+
+    #[test]
+    fn test_frames() {
+        let (_, seafloor) = parse::seafloor(TEST_INPUT).unwrap();
+        let frames = Day25::frames(&seafloor, 100);
+        assert_eq!(frames.len(), 59);
+        assert!(frames[58].starts_with("step 58, 0 moved\n"));
+    }
+}
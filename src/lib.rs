@@ -1,4 +1,9 @@
+pub use solution::{Part, Runner, Solution};
+
 pub mod util;
+pub mod bench;
+pub mod solution;
+pub mod verify;
 
 pub mod day01;
 pub mod day02;
@@ -13,4 +18,65 @@ pub mod day10;
 pub mod day11;
 pub mod day12;
 pub mod day13;
-pub mod day14;
\ No newline at end of file
+pub mod day14;
+pub mod day15;
+pub mod day16;
+pub mod day17;
+pub mod day18;
+pub mod day19;
+pub mod day20;
+pub mod day21;
+pub mod day22;
+pub mod day23;
+pub mod day24;
+pub mod day25;
+
+/// Every solved day, in order.
+pub static SOLUTIONS: &[&dyn Runner] = &[
+    &day01::Day01,
+    &day02::Day02,
+    &day03::Day03,
+    &day04::Day04,
+    &day05::Day05,
+    &day06::Day06,
+    &day07::Day07,
+    &day08::Day08,
+    &day09::Day09,
+    &day10::Day10,
+    &day11::Day11,
+    &day12::Day12,
+    &day13::Day13,
+    &day14::Day14,
+    &day15::Day15,
+    &day16::Day16,
+    &day17::Day17,
+    &day18::Day18,
+    &day19::Day19,
+    &day20::Day20,
+    &day21::Day21,
+    &day22::Day22,
+    &day23::Day23,
+    &day24::Day24,
+    &day25::Day25,
+];
+
+pub fn solution(day: u32) -> Option<&'static dyn Runner> {
+    SOLUTIONS.iter().copied().find(|s| s.day() == day)
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+
+    #[test]
+    fn test_registry_order() {
+        let days: Vec<u32> = SOLUTIONS.iter().map(|s| s.day()).collect();
+        assert_eq!(days, (1..=25).collect::<Vec<u32>>());
+    }
+
+    #[test]
+    fn test_lookup() {
+        assert_eq!(solution(9).map(|s| s.day()), Some(9));
+        assert!(solution(0).is_none());
+    }
+}
@@ -1,3 +1,255 @@
-fn main() {
-    println!("Hello, Advent of Code 2021!");
+use std::env;
+use std::error::Error;
+use std::path::PathBuf;
+use std::process::ExitCode;
+use std::thread::sleep;
+use std::time::Duration;
+
+use advent2021::{Part, Runner, solution, SOLUTIONS};
+use advent2021::bench::{self, CountingAllocator};
+use advent2021::util::{ansi, input_dir, input_path, InputError, puzzle_input_in, read_input, read_stdin};
+use advent2021::verify::{Outcome, verify_all};
+
+#[global_allocator]
+static ALLOCATOR: CountingAllocator = CountingAllocator;
+
+const USAGE: &str = "usage: advent2021 [--input-dir DIR | --input FILE] run <day|all> [part]
+       advent2021 [--input-dir DIR] verify
+       advent2021 [--input-dir DIR | --input FILE] bench [day|all] [--iterations N] [--json]
+       advent2021 [--input-dir DIR | --input FILE] show <day> [--frames N] [--delay MS]
+
+  --input-dir DIR  read dayNN.txt from DIR (default: $ADVENT_INPUT_DIR, then files/)
+  --input FILE     read a single day's input from FILE, or from stdin if FILE is -
+  --iterations N   how many times bench runs each stage (default: 10)
+  --json           print bench results as JSON instead of a table
+  --frames N       how many frames show draws at most (default: 100)
+  --delay MS       pause between frames in milliseconds (default: 100)";
+
+const DEFAULT_ITERATIONS: usize = 10;
+const DEFAULT_FRAMES: usize = 100;
+const DEFAULT_DELAY: Duration = Duration::from_millis(100);
+
+enum Source {
+    Dir(PathBuf),
+    File(PathBuf),
+    Stdin,
+}
+
+impl Source {
+    fn load(&self, day: u32) -> Result<String, InputError> {
+        match self {
+            Source::Dir(dir) => puzzle_input_in(dir, &format!("{:02}", day)),
+            Source::File(path) => read_input(path),
+            Source::Stdin => read_stdin(),
+        }
+    }
+}
+
+struct Options {
+    source: Source,
+    iterations: usize,
+    json: bool,
+    frames: usize,
+    delay: Duration,
+}
+
+fn print_answer(day: u32, part: Part, answer: &str) {
+    if answer.contains('\n') {
+        println!("day {:02} part {}:\n{}", day, part, answer);
+    } else {
+        println!("day {:02} part {}: {}", day, part, answer);
+    }
+}
+
+fn run(sol: &dyn Runner, source: &Source, parts: &[Part]) -> Result<(), Box<dyn Error>> {
+    let input = source.load(sol.day())?;
+    for &part in parts {
+        print_answer(sol.day(), part, &sol.run(&input, part)?);
+    }
+    Ok(())
+}
+
+fn verify(source: &Source) -> ExitCode {
+    let dir = match source {
+        Source::Dir(dir) => dir,
+        _ => {
+            eprintln!("verify reads every day's input, so it needs --input-dir rather than --input");
+            return ExitCode::FAILURE;
+        }
+    };
+    let checks = match verify_all(dir) {
+        Ok(checks) => checks,
+        Err(e) => {
+            eprintln!("{}", e);
+            return ExitCode::FAILURE;
+        }
+    };
+
+    let (mut passed, mut failed, mut missing) = (0, 0, 0);
+    for c in &checks {
+        let (status, detail) = match &c.outcome {
+            Outcome::Pass => { passed += 1; ("pass", String::new()) }
+            Outcome::Fail(actual) => { failed += 1; ("FAIL", format!("got {:?}", actual)) }
+            Outcome::Missing(actual) => { missing += 1; ("missing", format!("got {:?}", actual)) }
+            Outcome::Error(e) => { failed += 1; ("ERROR", e.clone()) }
+        };
+        let line = format!("day {:02} part {} {:<8} {:<8} {:>10.2?}  {}", c.day, c.part, c.input, status, c.elapsed, detail);
+        println!("{}", line.trim_end());
+    }
+    println!("{} passed, {} failed, {} missing", passed, failed, missing);
+
+    if failed > 0 { ExitCode::FAILURE } else { ExitCode::SUCCESS }
+}
+
+/// When running every day, the ones without an input file yet are skipped rather than stopping the whole run.
+fn skip_missing(solutions: &[&'static dyn Runner], source: &Source) -> Vec<&'static dyn Runner> {
+    let Source::Dir(dir) = source else { return solutions.to_vec() };
+    solutions.iter().copied().filter(|sol| {
+        let present = input_path(dir, &format!("{:02}", sol.day())).exists();
+        if !present {
+            eprintln!("day {:02}: no input, skipped", sol.day());
+        }
+        present
+    }).collect()
+}
+
+fn bench(solutions: &[&dyn Runner], opts: &Options) -> Result<(), Box<dyn Error>> {
+    let mut results = Vec::with_capacity(solutions.len());
+    for sol in solutions {
+        let input = opts.source.load(sol.day())?;
+        results.push(sol.bench(&input, opts.iterations)?);
+    }
+    if opts.json {
+        print!("{}", bench::json(&results));
+    } else {
+        print!("{}", bench::table(&results));
+    }
+    Ok(())
+}
+
+fn show(sol: &dyn Runner, opts: &Options) -> Result<(), Box<dyn Error>> {
+    let input = opts.source.load(sol.day())?;
+    let frames = sol.frames(&input, opts.frames)?;
+    if frames.is_empty() {
+        return Err(format!("day {:02} has nothing to show", sol.day()).into());
+    }
+    for (i, frame) in frames.iter().enumerate() {
+        if i > 0 {
+            sleep(opts.delay);
+        }
+        print!("{}{}", ansi::CLEAR_SCREEN, frame);
+    }
+    Ok(())
+}
+
+/// Pulls the `--` options out of `args`, leaving only the positional arguments behind.
+fn take_options(args: &mut Vec<String>) -> Result<Options, String> {
+    let mut opts = Options {
+        source: Source::Dir(input_dir()),
+        iterations: DEFAULT_ITERATIONS,
+        json: false,
+        frames: DEFAULT_FRAMES,
+        delay: DEFAULT_DELAY,
+    };
+    while let Some(i) = args.iter().position(|a| a.starts_with("--")) {
+        let flag = args.remove(i);
+        if flag == "--json" {
+            opts.json = true;
+            continue;
+        }
+        if i >= args.len() {
+            return Err(format!("{} needs a value", flag));
+        }
+        let value = args.remove(i);
+        match (flag.as_str(), value.as_str()) {
+            ("--input-dir", dir) => opts.source = Source::Dir(PathBuf::from(dir)),
+            ("--input", "-") => opts.source = Source::Stdin,
+            ("--input", file) => opts.source = Source::File(PathBuf::from(file)),
+            ("--iterations", n) => match n.parse() {
+                Ok(n) if n > 0 => opts.iterations = n,
+                _ => return Err(format!("--iterations needs a positive number, not {}", n))
+            },
+            ("--frames", n) => match n.parse() {
+                Ok(n) if n > 0 => opts.frames = n,
+                _ => return Err(format!("--frames needs a positive number, not {}", n))
+            },
+            ("--delay", ms) => match ms.parse() {
+                Ok(ms) => opts.delay = Duration::from_millis(ms),
+                _ => return Err(format!("--delay needs a number of milliseconds, not {}", ms))
+            },
+            _ => return Err(format!("unknown option {}", flag))
+        };
+    }
+    Ok(opts)
+}
+
+fn select(day: &str) -> Result<Vec<&'static dyn Runner>, String> {
+    if day == "all" {
+        return Ok(SOLUTIONS.to_vec());
+    }
+    match day.parse().ok().and_then(solution) {
+        Some(sol) => Ok(vec![sol]),
+        None => Err(format!("unknown day {}", day))
+    }
+}
+
+fn main() -> ExitCode {
+    let mut args: Vec<String> = env::args().skip(1).collect();
+    let opts = match take_options(&mut args) {
+        Ok(opts) => opts,
+        Err(e) => {
+            eprintln!("{}\n{}", e, USAGE);
+            return ExitCode::FAILURE;
+        }
+    };
+    let args: Vec<&str> = args.iter().map(String::as_str).collect();
+
+    let (command, day, part) = match args.as_slice() {
+        ["verify"] => return verify(&opts.source),
+        ["bench"] => ("bench", "all", None),
+        ["bench", day] => ("bench", *day, None),
+        ["show", day] if *day != "all" => ("show", *day, None),
+        ["run", day] => ("run", *day, None),
+        ["run", day, part] => ("run", *day, Some(*part)),
+        _ => {
+            eprintln!("{}", USAGE);
+            return ExitCode::FAILURE;
+        }
+    };
+
+    let parts: Vec<Part> = match part.map(|p| p.parse().ok().and_then(Part::from_number)) {
+        None => Part::BOTH.to_vec(),
+        Some(Some(p)) => vec![p],
+        Some(None) => {
+            eprintln!("part must be 1 or 2\n{}", USAGE);
+            return ExitCode::FAILURE;
+        }
+    };
+
+    let solutions = match select(day) {
+        Ok(solutions) if day == "all" => skip_missing(&solutions, &opts.source),
+        Ok(solutions) => solutions,
+        Err(e) => {
+            eprintln!("{}\n{}", e, USAGE);
+            return ExitCode::FAILURE;
+        }
+    };
+
+    if solutions.len() > 1 && !matches!(opts.source, Source::Dir(_)) {
+        eprintln!("--input only makes sense for a single day\n{}", USAGE);
+        return ExitCode::FAILURE;
+    }
+
+    let result = match command {
+        "bench" => bench(&solutions, &opts),
+        "show" => show(solutions[0], &opts),
+        _ => solutions.iter().try_for_each(|&sol| run(sol, &opts.source, &parts))
+    };
+    match result {
+        Ok(()) => ExitCode::SUCCESS,
+        Err(e) => {
+            eprintln!("{}", e);
+            ExitCode::FAILURE
+        }
+    }
 }
@@ -0,0 +1,95 @@
+use std::fmt::{Display, Formatter};
+
+use crate::bench::{DayBench, measure};
+use crate::util::ParseError;
+
+#[derive(Clone, Copy, Debug, Eq, PartialEq, Hash)]
+pub enum Part {
+    One,
+    Two,
+}
+
+impl Part {
+    pub const BOTH: [Part; 2] = [Part::One, Part::Two];
+
+    pub fn number(self) -> u32 {
+        match self {
+            Part::One => 1,
+            Part::Two => 2,
+        }
+    }
+
+    pub fn from_number(n: u32) -> Option<Part> {
+        match n {
+            1 => Some(Part::One),
+            2 => Some(Part::Two),
+            _ => None
+        }
+    }
+}
+
+impl Display for Part {
+    fn fmt(&self, f: &mut Formatter<'_>) -> std::fmt::Result {
+        write!(f, "{}", self.number())
+    }
+}
+
+/// A single day's puzzle: how to read the input, and how to answer each part from it.
+pub trait Solution {
+    const DAY: u32;
+
+    type Input;
+    type Answer1: Display;
+    type Answer2: Display;
+
+    fn parse(input: &str) -> Result<Self::Input, ParseError>;
+    fn part1(input: &Self::Input) -> Self::Answer1;
+    fn part2(input: &Self::Input) -> Self::Answer2;
+
+    /// ANSI-coloured pictures of the puzzle, at most `limit` of them, for days that have something worth watching.
+    fn frames(_input: &Self::Input, _limit: usize) -> Vec<String> {
+        Vec::new()
+    }
+}
+
+/// Type-erased view of a `Solution`, so days with different input and answer types can live in one registry.
+pub trait Runner: Sync {
+    fn day(&self) -> u32;
+    fn run(&self, input: &str, part: Part) -> Result<String, ParseError>;
+    fn run_both(&self, input: &str) -> Result<(String, String), ParseError>;
+    fn bench(&self, input: &str, iterations: usize) -> Result<DayBench, ParseError>;
+    fn frames(&self, input: &str, limit: usize) -> Result<Vec<String>, ParseError>;
+}
+
+impl<S: Solution + Sync> Runner for S {
+    fn day(&self) -> u32 {
+        S::DAY
+    }
+
+    fn run(&self, input: &str, part: Part) -> Result<String, ParseError> {
+        let parsed = S::parse(input)?;
+        Ok(match part {
+            Part::One => S::part1(&parsed).to_string(),
+            Part::Two => S::part2(&parsed).to_string(),
+        })
+    }
+
+    fn run_both(&self, input: &str) -> Result<(String, String), ParseError> {
+        let parsed = S::parse(input)?;
+        Ok((S::part1(&parsed).to_string(), S::part2(&parsed).to_string()))
+    }
+
+    fn bench(&self, input: &str, iterations: usize) -> Result<DayBench, ParseError> {
+        let parsed = S::parse(input)?;
+        Ok(DayBench {
+            day: S::DAY,
+            parse: measure(iterations, || S::parse(input)),
+            part1: measure(iterations, || S::part1(&parsed)),
+            part2: measure(iterations, || S::part2(&parsed)),
+        })
+    }
+
+    fn frames(&self, input: &str, limit: usize) -> Result<Vec<String>, ParseError> {
+        Ok(S::frames(&S::parse(input)?, limit))
+    }
+}
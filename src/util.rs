@@ -1,7 +1,319 @@
-pub fn puzzle_input(day: &str) -> String {
-    let filename = format!("files/day{}.txt", day);
-    let path = std::path::Path::new(&filename);
-    std::fs::read_to_string(&path).map_err(|e| {
-        panic!("Error opening {}: {}", path.display(), e)
-    }).unwrap()
+use std::fmt::{Display, Formatter};
+use std::io::{self, Read};
+use std::path::{Path, PathBuf};
+
+use nom::error::{Error, ErrorKind};
+use nom::{IResult, Parser};
+
+pub use bitset::BitSet;
+pub use grid::Grid;
+
+pub mod ansi;
+pub mod bitset;
+pub mod grid;
+
+/// Environment variable that overrides the directory puzzle inputs are read from.
+pub const INPUT_DIR_VAR: &str = "ADVENT_INPUT_DIR";
+
+#[derive(Debug)]
+pub enum InputError {
+    Missing(PathBuf),
+    Unreadable(PathBuf, io::Error),
+    NotUtf8(PathBuf),
+    Empty(PathBuf),
+}
+
+impl Display for InputError {
+    fn fmt(&self, f: &mut Formatter<'_>) -> std::fmt::Result {
+        match self {
+            InputError::Missing(path) => write!(f, "input file {} does not exist", path.display()),
+            InputError::Unreadable(path, e) => write!(f, "couldn't read {}: {}", path.display(), e),
+            InputError::NotUtf8(path) => write!(f, "{} is not valid UTF-8", path.display()),
+            InputError::Empty(path) => write!(f, "{} is empty", path.display()),
+        }
+    }
+}
+
+impl std::error::Error for InputError {
+    fn source(&self) -> Option<&(dyn std::error::Error + 'static)> {
+        match self {
+            InputError::Unreadable(_, e) => Some(e),
+            _ => None
+        }
+    }
+}
+
+/// The input directory: `$ADVENT_INPUT_DIR` if set, otherwise `files/` in the crate root.
+pub fn input_dir() -> PathBuf {
+    match std::env::var_os(INPUT_DIR_VAR) {
+        Some(dir) => PathBuf::from(dir),
+        None => Path::new(env!("CARGO_MANIFEST_DIR")).join("files")
+    }
+}
+
+pub fn input_path(dir: &Path, day: &str) -> PathBuf {
+    dir.join(format!("day{}.txt", day))
+}
+
+fn checked(path: &Path, bytes: Vec<u8>) -> Result<String, InputError> {
+    let text = String::from_utf8(bytes).map_err(|_| InputError::NotUtf8(path.to_owned()))?;
+    if text.trim().is_empty() {
+        return Err(InputError::Empty(path.to_owned()));
+    }
+    Ok(text)
+}
+
+pub fn read_input(path: &Path) -> Result<String, InputError> {
+    let bytes = std::fs::read(path).map_err(|e| match e.kind() {
+        io::ErrorKind::NotFound => InputError::Missing(path.to_owned()),
+        _ => InputError::Unreadable(path.to_owned(), e)
+    })?;
+    checked(path, bytes)
+}
+
+pub fn read_stdin() -> Result<String, InputError> {
+    let path = Path::new("<stdin>");
+    let mut bytes = Vec::new();
+    io::stdin().read_to_end(&mut bytes).map_err(|e| InputError::Unreadable(path.to_owned(), e))?;
+    checked(path, bytes)
+}
+
+pub fn puzzle_input_in(dir: &Path, day: &str) -> Result<String, InputError> {
+    read_input(&input_path(dir, day))
+}
+
+pub fn puzzle_input(day: &str) -> Result<String, InputError> {
+    puzzle_input_in(&input_dir(), day)
+}
+
+/// The frames of a simulation for show mode: `state` rendered as it is now and after each step, `limit` frames in all.
+/// `step` advances the state and returns what changed, which `render` gets along with the step number, and whether
+/// that was the last step worth showing.
+pub fn frames<S, C: Default>(state: &mut S, limit: usize, mut step: impl FnMut(&mut S) -> (C, bool),
+                             mut render: impl FnMut(&S, usize, &C) -> String) -> Vec<String> {
+    let mut frames = Vec::with_capacity(limit);
+    let (mut change, mut finished) = (C::default(), false);
+    while frames.len() < limit {
+        frames.push(render(state, frames.len(), &change));
+        if finished {
+            break;
+        }
+        (change, finished) = step(state);
+    }
+    frames
+}
+
+/// Where and why a day's input failed to parse. Lines and columns count from 1.
+#[derive(Debug, PartialEq, Eq)]
+pub struct ParseError {
+    pub day: u32,
+    pub line: usize,
+    pub column: usize,
+    pub snippet: String,
+    pub expected: String,
+}
+
+impl Display for ParseError {
+    fn fmt(&self, f: &mut Formatter<'_>) -> std::fmt::Result {
+        write!(f, "day {:02}, line {}, column {}: expected {}, found ",
+               self.day, self.line, self.column, self.expected)?;
+        if self.snippet.is_empty() {
+            write!(f, "end of input")
+        } else {
+            write!(f, "{:?}", self.snippet)
+        }
+    }
+}
+
+impl std::error::Error for ParseError {}
+
+const SNIPPET_LEN: usize = 20;
+
+fn describe(kind: ErrorKind) -> String {
+    match kind {
+        ErrorKind::Digit => "a number".to_owned(),
+        ErrorKind::Alpha => "letters".to_owned(),
+        ErrorKind::Tag => "a keyword or separator".to_owned(),
+        ErrorKind::Char => "a line break or separator".to_owned(),
+        ErrorKind::CrLf => "a line break".to_owned(),
+        ErrorKind::OneOf => "one of the allowed characters".to_owned(),
+        ErrorKind::MapRes | ErrorKind::MapOpt => "a valid value".to_owned(),
+        ErrorKind::Eof => "end of input".to_owned(),
+        ErrorKind::Complete => "more input".to_owned(),
+        ErrorKind::TooLarge => "a smaller value".to_owned(),
+        other => other.description().to_lowercase()
+    }
+}
+
+impl ParseError {
+    /// Builds an error pointing at `rest`, which must be a suffix of `input`.
+    fn at(day: u32, input: &str, rest: &str, expected: String) -> ParseError {
+        let offset = input.len() - rest.len();
+        let before = &input[..offset];
+        let line = before.matches('\n').count() + 1;
+        let column = before.rsplit('\n').next().unwrap_or("").chars().count() + 1;
+        let snippet = match rest.lines().next() {
+            Some("") => rest.chars().take(1).collect(),
+            Some(line) => line.chars().take(SNIPPET_LEN).collect(),
+            None => String::new()
+        };
+        ParseError { day, line, column, snippet, expected }
+    }
+}
+
+/// Like nom's `separated_list1`, for inputs with an item per line. Once a separator is followed by anything other than
+/// a blank line or the end of the input, the item there has to parse, and an item has to be followed by a separator
+/// unless the rest of its line is blank. Either error is passed on as a failure, rather than the list quietly ending
+/// early and the whole bad line being reported as leftover input.
+pub fn lines1<'a, O, O2, S, P>(mut sep: S, mut item: P) -> impl FnMut(&'a str) -> IResult<&'a str, Vec<O>>
+    where S: Parser<&'a str, O2, Error<&'a str>>, P: Parser<&'a str, O, Error<&'a str>> {
+    move |input| {
+        let (mut input, first) = item.parse(input)?;
+        let mut items = vec![first];
+        loop {
+            let rest = match sep.parse(input) {
+                Ok((rest, _)) => rest,
+                // Something other than a separator straight after an item, on the same line, is part of a bad line
+                Err(nom::Err::Error(e)) if input.lines().next().is_some_and(|line| !line.trim().is_empty()) =>
+                    return Err(nom::Err::Failure(e)),
+                Err(nom::Err::Error(_)) => return Ok((input, items)),
+                Err(e) => return Err(e),
+            };
+            if rest.lines().next().is_none_or(|line| line.trim().is_empty()) {
+                return Ok((input, items));
+            }
+            let (rest, next) = item.parse(rest).map_err(|e| match e {
+                nom::Err::Error(e) => nom::Err::Failure(e),
+                other => other,
+            })?;
+            items.push(next);
+            input = rest;
+        }
+    }
+}
+
+/// Runs a day's top-level parser over the whole of `input`.
+///
+/// Unlike calling the parser directly, anything left over other than a single final newline is an error rather than
+/// being silently dropped.
+pub fn parse_all<'a, T, P>(day: u32, input: &'a str, mut parser: P) -> Result<T, ParseError>
+    where P: FnMut(&'a str) -> IResult<&'a str, T> {
+    match parser(input) {
+        Ok((rest, value)) => {
+            if rest.is_empty() || rest == "\n" || rest == "\r\n" {
+                Ok(value)
+            } else {
+                // Point at the first leftover line with something on it, if there is one
+                let garbage = match rest.trim_start_matches(['\n', '\r']) {
+                    "" => rest,
+                    trimmed => trimmed
+                };
+                Err(ParseError::at(day, input, garbage, describe(ErrorKind::Eof)))
+            }
+        }
+        Err(nom::Err::Error(e) | nom::Err::Failure(e)) =>
+            Err(ParseError::at(day, input, e.input, describe(e.code))),
+        Err(nom::Err::Incomplete(_)) =>
+            Err(ParseError::at(day, input, "", "more input".to_owned())),
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use nom::character::complete::{char, newline, u32 as num};
+    use nom::multi::separated_list1;
+    use nom::sequence::separated_pair;
+
+    use super::*;
+
+    fn numbers(input: &str) -> IResult<&str, Vec<u32>> {
+        separated_list1(newline, num)(input)
+    }
+
+    fn scratch_file(name: &str, contents: &[u8]) -> PathBuf {
+        let path = std::env::temp_dir().join(format!("advent2021-{}-{}", std::process::id(), name));
+        std::fs::write(&path, contents).unwrap();
+        path
+    }
+
+    #[test]
+    fn test_read_input() {
+        let path = scratch_file("ok.txt", b"1\n2\n");
+        assert_eq!(read_input(&path).unwrap(), "1\n2\n");
+    }
+
+    #[test]
+    fn test_missing() {
+        let err = puzzle_input_in(Path::new("no/such/dir"), "01").unwrap_err();
+        assert!(matches!(err, InputError::Missing(p) if p.ends_with("day01.txt")));
+    }
+
+    #[test]
+    fn test_not_utf8() {
+        let path = scratch_file("bad.txt", &[0x31, 0xff, 0x0a]);
+        assert!(matches!(read_input(&path), Err(InputError::NotUtf8(_))));
+    }
+
+    #[test]
+    fn test_empty() {
+        let path = scratch_file("empty.txt", b"\n\n");
+        assert!(matches!(read_input(&path), Err(InputError::Empty(_))));
+    }
+
+    #[test]
+    fn test_frames() {
+        let countdown = |n: &mut u32| { *n -= 1; (1, *n == 0) };
+        let render = |n: &u32, step: usize, change: &u32| format!("{} {} {}", step, change, n);
+        assert_eq!(frames(&mut 2, 10, countdown, render), ["0 0 2", "1 1 1", "2 1 0"]);
+        assert_eq!(frames(&mut 5, 2, countdown, render), ["0 0 5", "1 1 4"]);
+        assert!(frames(&mut 5, 0, countdown, render).is_empty());
+    }
+
+    #[test]
+    fn test_lines1() {
+        assert_eq!(lines1(newline, num)("1\n2\n\n3"), Ok(("\n\n3", vec![1, 2])));
+        assert_eq!(lines1(newline, num)("1\n2\n"), Ok(("\n", vec![1, 2])));
+        assert!(matches!(lines1(newline, num)("1\nx"), Err(nom::Err::Failure(e)) if e.input == "x"));
+
+        let err = parse_all(1, "1 2\n3 4\n5 x", lines1(newline, separated_pair(num, char(' '), num))).unwrap_err();
+        assert_eq!((err.line, err.column, err.expected.as_str()), (3, 3, "a number"));
+    }
+
+    #[test]
+    fn test_parse_all() {
+        assert_eq!(parse_all(1, "1\n2\n", numbers), Ok(vec![1, 2]));
+        assert_eq!(parse_all(1, "1\n2", numbers), Ok(vec![1, 2]));
+    }
+
+    #[test]
+    fn test_parse_error_position() {
+        let err = parse_all(5, "x\n2\n", numbers).unwrap_err();
+        assert_eq!(err, ParseError {
+            day: 5,
+            line: 1,
+            column: 1,
+            snippet: "x".to_owned(),
+            expected: "a number".to_owned(),
+        });
+    }
+
+    #[test]
+    fn test_trailing_garbage() {
+        let err = parse_all(5, "1\n2\n3 oops\n", numbers).unwrap_err();
+        assert_eq!((err.line, err.column), (3, 2));
+        assert_eq!(err.snippet, " oops");
+        assert_eq!(err.expected, "end of input");
+        assert_eq!(err.to_string(), "day 05, line 3, column 2: expected end of input, found \" oops\"");
+    }
+
+    #[test]
+    fn test_trailing_lines() {
+        let err = parse_all(1, "1\n2\n\nabc", numbers).unwrap_err();
+        assert_eq!((err.line, err.column), (4, 1));
+        assert_eq!(err.snippet, "abc");
+
+        let err = parse_all(1, "1\n2\n\n", numbers).unwrap_err();
+        assert_eq!((err.line, err.column), (2, 2));
+        assert_eq!(err.snippet, "\n");
+    }
 }
@@ -0,0 +1,51 @@
+use std::fmt::Display;
+
+pub const RESET: &str = "\x1b[0m";
+pub const BOLD: &str = "\x1b[1m";
+pub const DIM: &str = "\x1b[2m";
+
+/// Clears the terminal and moves the cursor to the top left, ready for the next frame.
+pub const CLEAR_SCREEN: &str = "\x1b[2J\x1b[H";
+
+/// Foreground colour from the 256-colour palette.
+pub fn fg(colour: u8) -> String {
+    format!("\x1b[38;5;{}m", colour)
+}
+
+/// Background colour from the 256-colour palette.
+pub fn bg(colour: u8) -> String {
+    format!("\x1b[48;5;{}m", colour)
+}
+
+pub fn paint(text: impl Display, style: &str) -> String {
+    format!("{}{}{}", style, text, RESET)
+}
+
+/// Removes escape sequences, leaving the text that would actually be shown.
+pub fn strip(text: &str) -> String {
+    let mut out = String::with_capacity(text.len());
+    let mut chars = text.chars();
+    while let Some(ch) = chars.next() {
+        if ch == '\x1b' {
+            // Skip the '[', parameters and the final letter
+            for c in chars.by_ref() {
+                if c.is_ascii_alphabetic() { break; }
+            }
+        } else {
+            out.push(ch);
+        }
+    }
+    out
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+
+    #[test]
+    fn test_strip() {
+        let painted = format!("{}{}", paint(1, &fg(226)), paint("ab", BOLD));
+        assert_eq!(strip(&painted), "1ab");
+        assert_eq!(strip(&format!("{}x\n", CLEAR_SCREEN)), "x\n");
+    }
+}
@@ -0,0 +1,67 @@
+const WORD_BITS: usize = u64::BITS as usize;
+
+/// A fixed-capacity set of small integers, one bit each.
+#[derive(Clone, Debug, PartialEq, Eq, Hash)]
+pub struct BitSet {
+    words: Vec<u64>,
+    capacity: usize,
+}
+
+impl BitSet {
+    pub fn new(capacity: usize) -> BitSet {
+        BitSet { words: vec![0; capacity.div_ceil(WORD_BITS)], capacity }
+    }
+
+    pub fn capacity(&self) -> usize {
+        self.capacity
+    }
+
+    pub fn contains(&self, i: usize) -> bool {
+        i < self.capacity && self.words[i / WORD_BITS] & (1 << (i % WORD_BITS)) != 0
+    }
+
+    /// Adds `i` to the set, returning whether it was newly added.
+    pub fn insert(&mut self, i: usize) -> bool {
+        assert!(i < self.capacity, "{} doesn't fit in a bitset of {}", i, self.capacity);
+        let word = &mut self.words[i / WORD_BITS];
+        let mask = 1 << (i % WORD_BITS);
+        let added = *word & mask == 0;
+        *word |= mask;
+        added
+    }
+
+    pub fn len(&self) -> usize {
+        self.words.iter().map(|w| w.count_ones() as usize).sum()
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.words.iter().all(|&w| w == 0)
+    }
+
+    pub fn clear(&mut self) {
+        self.words.fill(0);
+    }
+
+    pub fn iter(&self) -> impl Iterator<Item=usize> + '_ {
+        (0..self.capacity).filter(move |&i| self.contains(i))
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+
+    #[test]
+    fn test_insert() {
+        let mut set = BitSet::new(200);
+        assert!(set.insert(3));
+        assert!(set.insert(150));
+        assert!(!set.insert(150));
+        assert!(set.contains(150) && !set.contains(149) && !set.contains(500));
+        assert_eq!(set.iter().collect::<Vec<_>>(), [3, 150]);
+        assert_eq!(set.len(), 2);
+
+        set.clear();
+        assert!(set.is_empty());
+    }
+}
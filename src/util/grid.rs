@@ -0,0 +1,225 @@
+use std::fmt::{Display, Formatter};
+use std::ops::{Index, IndexMut};
+
+/// A cell position, as `(x, y)` with `(0, 0)` in the top left.
+pub type Pos = (usize, usize);
+
+const OFFSETS_4: [(isize, isize); 4] = [(0, -1), (-1, 0), (1, 0), (0, 1)];
+const OFFSETS_8: [(isize, isize); 8] = [(-1, -1), (0, -1), (1, -1), (-1, 0), (1, 0), (-1, 1), (0, 1), (1, 1)];
+
+/// A rectangular grid of cells stored row by row.
+#[derive(Clone, Debug, PartialEq, Eq, Hash)]
+pub struct Grid<T> {
+    width: usize,
+    height: usize,
+    cells: Vec<T>,
+}
+
+impl<T> Grid<T> {
+    pub fn from_vec(width: usize, height: usize, cells: Vec<T>) -> Grid<T> {
+        assert_eq!(cells.len(), width * height, "{}x{} grid needs {} cells", width, height, width * height);
+        Grid { width, height, cells }
+    }
+
+    /// Builds a grid from its rows, or `None` if they aren't all the same length.
+    pub fn from_rows(rows: Vec<Vec<T>>) -> Option<Grid<T>> {
+        let width = rows.first().map_or(0, Vec::len);
+        if rows.iter().any(|row| row.len() != width) {
+            return None;
+        }
+        let height = rows.len();
+        Some(Grid { width, height, cells: rows.into_iter().flatten().collect() })
+    }
+
+    pub fn width(&self) -> usize {
+        self.width
+    }
+
+    pub fn height(&self) -> usize {
+        self.height
+    }
+
+    pub fn len(&self) -> usize {
+        self.cells.len()
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.cells.is_empty()
+    }
+
+    pub fn contains(&self, (x, y): Pos) -> bool {
+        x < self.width && y < self.height
+    }
+
+    /// Position of `pos` in the row-by-row cell order.
+    pub fn index(&self, (x, y): Pos) -> usize {
+        y * self.width + x
+    }
+
+    pub fn pos(&self, index: usize) -> Pos {
+        (index % self.width, index / self.width)
+    }
+
+    pub fn get(&self, pos: Pos) -> Option<&T> {
+        if self.contains(pos) { Some(&self.cells[self.index(pos)]) } else { None }
+    }
+
+    pub fn get_mut(&mut self, pos: Pos) -> Option<&mut T> {
+        if self.contains(pos) {
+            let i = self.index(pos);
+            Some(&mut self.cells[i])
+        } else {
+            None
+        }
+    }
+
+    /// Replaces the cell at `pos`, returning the old value, or `None` (dropping `value`) if `pos` is off the grid.
+    pub fn set(&mut self, pos: Pos, value: T) -> Option<T> {
+        self.get_mut(pos).map(|cell| std::mem::replace(cell, value))
+    }
+
+    pub fn cells(&self) -> &[T] {
+        &self.cells
+    }
+
+    /// Every position, row by row.
+    pub fn positions(&self) -> impl Iterator<Item=Pos> {
+        let (width, height) = (self.width, self.height);
+        (0..height).flat_map(move |y| (0..width).map(move |x| (x, y)))
+    }
+
+    pub fn row(&self, y: usize) -> &[T] {
+        &self.cells[y * self.width..(y + 1) * self.width]
+    }
+
+    pub fn rows(&self) -> impl Iterator<Item=&[T]> {
+        self.cells.chunks(self.width.max(1))
+    }
+
+    pub fn column(&self, x: usize) -> impl Iterator<Item=&T> {
+        self.cells.iter().skip(x).step_by(self.width.max(1))
+    }
+
+    pub fn columns(&self) -> impl Iterator<Item=impl Iterator<Item=&T>> {
+        (0..self.width).map(move |x| self.column(x))
+    }
+
+    fn offsets(&self, (x, y): Pos, offsets: &'static [(isize, isize)]) -> impl Iterator<Item=Pos> {
+        let (width, height) = (self.width, self.height);
+        offsets.iter().filter_map(move |&(dx, dy)| {
+            let nx = x.checked_add_signed(dx)?;
+            let ny = y.checked_add_signed(dy)?;
+            if nx < width && ny < height { Some((nx, ny)) } else { None }
+        })
+    }
+
+    /// The up to four orthogonally adjacent positions that are on the grid.
+    pub fn neighbours4(&self, pos: Pos) -> impl Iterator<Item=Pos> {
+        self.offsets(pos, &OFFSETS_4)
+    }
+
+    /// The up to eight adjacent positions, diagonals included, that are on the grid.
+    pub fn neighbours8(&self, pos: Pos) -> impl Iterator<Item=Pos> {
+        self.offsets(pos, &OFFSETS_8)
+    }
+
+    pub fn map<U>(&self, f: impl FnMut(&T) -> U) -> Grid<U> {
+        Grid { width: self.width, height: self.height, cells: self.cells.iter().map(f).collect() }
+    }
+}
+
+impl<T: Clone> Grid<T> {
+    pub fn filled(width: usize, height: usize, value: T) -> Grid<T> {
+        Grid { width, height, cells: vec![value; width * height] }
+    }
+}
+
+impl<T> Index<Pos> for Grid<T> {
+    type Output = T;
+
+    fn index(&self, pos: Pos) -> &T {
+        assert!(self.contains(pos), "{:?} is outside a {}x{} grid", pos, self.width, self.height);
+        &self.cells[Grid::index(self, pos)]
+    }
+}
+
+impl<T> IndexMut<Pos> for Grid<T> {
+    fn index_mut(&mut self, pos: Pos) -> &mut T {
+        assert!(self.contains(pos), "{:?} is outside a {}x{} grid", pos, self.width, self.height);
+        let i = Grid::index(self, pos);
+        &mut self.cells[i]
+    }
+}
+
+impl<T: Display> Display for Grid<T> {
+    fn fmt(&self, f: &mut Formatter<'_>) -> std::fmt::Result {
+        for row in self.rows() {
+            for cell in row {
+                write!(f, "{}", cell)?;
+            }
+            writeln!(f)?;
+        }
+        Ok(())
+    }
+}
+
+pub mod parse {
+    use nom::character::complete::{newline, one_of};
+    use nom::combinator::{map, map_opt};
+    use nom::IResult;
+    use nom::multi::{many1, separated_list1};
+
+    use super::Grid;
+
+    fn row(input: &str) -> IResult<&str, Vec<u8>> {
+        many1(map(one_of("0123456789"), |c: char| c.to_digit(10).unwrap() as u8))(input)
+    }
+
+    /// Lines of single digits, all the same length.
+    pub fn digits(input: &str) -> IResult<&str, Grid<u8>> {
+        map_opt(separated_list1(newline, row), Grid::from_rows)(input)
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+
+    const TEST_INPUT: &str = "123\n456";
+
+    #[test]
+    fn test_parse() {
+        let (_, grid) = parse::digits(TEST_INPUT).unwrap();
+        assert_eq!((grid.width(), grid.height()), (3, 2));
+        assert_eq!(grid[(0, 0)], 1);
+        assert_eq!(grid[(2, 1)], 6);
+        assert_eq!(grid.to_string(), "123\n456\n");
+        assert!(parse::digits("12\n345").is_err());
+    }
+
+    #[test]
+    fn test_get_set() {
+        let (_, mut grid) = parse::digits(TEST_INPUT).unwrap();
+        assert_eq!(grid.get((3, 0)), None);
+        assert_eq!(grid.set((1, 1), 0), Some(5));
+        assert_eq!(grid.set((1, 2), 0), None);
+        assert_eq!(grid.row(1), [4, 0, 6]);
+    }
+
+    #[test]
+    fn test_rows_columns() {
+        let (_, grid) = parse::digits(TEST_INPUT).unwrap();
+        assert_eq!(grid.rows().collect::<Vec<_>>(), [[1, 2, 3], [4, 5, 6]]);
+        let columns: Vec<Vec<u8>> = grid.columns().map(|c| c.copied().collect()).collect();
+        assert_eq!(columns, [[1, 4], [2, 5], [3, 6]]);
+    }
+
+    #[test]
+    fn test_neighbours() {
+        let grid = Grid::filled(3, 3, 0);
+        assert_eq!(grid.neighbours4((0, 0)).collect::<Vec<_>>(), [(1, 0), (0, 1)]);
+        assert_eq!(grid.neighbours4((1, 1)).count(), 4);
+        assert_eq!(grid.neighbours8((2, 2)).collect::<Vec<_>>(), [(1, 1), (2, 1), (1, 2)]);
+        assert_eq!(grid.neighbours8((1, 1)).count(), 8);
+    }
+}
@@ -0,0 +1,237 @@
+use std::fmt::{Display, Formatter};
+use std::path::{Path, PathBuf};
+use std::time::{Duration, Instant};
+
+use crate::{Part, Runner, SOLUTIONS};
+use crate::util::{InputError, parse_all, ParseError, read_input};
+
+/// File in the input directory that records the known-correct answers for that set of inputs.
+pub const ANSWERS_FILE: &str = "answers.txt";
+
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct Answer {
+    pub day: u32,
+    pub part: Part,
+    pub input: String,
+    pub expected: String,
+}
+
+#[derive(Debug)]
+pub enum AnswersError {
+    Input(InputError),
+    Parse(PathBuf, ParseError),
+}
+
+impl Display for AnswersError {
+    fn fmt(&self, f: &mut Formatter<'_>) -> std::fmt::Result {
+        match self {
+            AnswersError::Input(e) => write!(f, "{}", e),
+            AnswersError::Parse(path, e) => write!(f, "{}, line {}, column {}: expected {}, found {:?}",
+                                                   path.display(), e.line, e.column, e.expected, e.snippet),
+        }
+    }
+}
+
+impl std::error::Error for AnswersError {}
+
+#[derive(Debug, PartialEq, Eq)]
+pub enum Outcome {
+    Pass,
+    Fail(String),
+    Missing(String),
+    Error(String),
+}
+
+#[derive(Debug)]
+pub struct Check {
+    pub day: u32,
+    pub part: Part,
+    pub input: String,
+    pub outcome: Outcome,
+    pub elapsed: Duration,
+}
+
+/// The input id used when a day has no recorded answers: its usual `dayNN` file.
+pub fn default_input_id(day: u32) -> String {
+    format!("day{:02}", day)
+}
+
+pub fn load_answers(dir: &Path) -> Result<Vec<Answer>, AnswersError> {
+    let path = dir.join(ANSWERS_FILE);
+    let text = read_input(&path).map_err(AnswersError::Input)?;
+    parse_all(0, &text, parse::answers).map_err(|e| AnswersError::Parse(path, e))
+}
+
+fn expected<'a>(answers: &'a [Answer], day: u32, part: Part, input: &str) -> Option<&'a str> {
+    answers.iter()
+        .find(|a| a.day == day && a.part == part && a.input == input)
+        .map(|a| a.expected.as_str())
+}
+
+fn input_ids(answers: &[Answer], day: u32) -> Vec<String> {
+    let mut ids: Vec<String> = Vec::new();
+    for a in answers.iter().filter(|a| a.day == day) {
+        if !ids.contains(&a.input) {
+            ids.push(a.input.clone());
+        }
+    }
+    if ids.is_empty() {
+        ids.push(default_input_id(day));
+    }
+    ids
+}
+
+fn check(sol: &dyn Runner, part: Part, id: &str, input: &Result<String, InputError>, answers: &[Answer]) -> Check {
+    let start = Instant::now();
+    let outcome = match input {
+        Err(e) => Outcome::Error(e.to_string()),
+        Ok(text) => match (sol.run(text, part), expected(answers, sol.day(), part, id)) {
+            (Err(e), _) => Outcome::Error(e.to_string()),
+            (Ok(actual), None) => Outcome::Missing(actual),
+            (Ok(actual), Some(exp)) if actual == exp => Outcome::Pass,
+            (Ok(actual), Some(_)) => Outcome::Fail(actual),
+        }
+    };
+    Check { day: sol.day(), part, input: id.to_owned(), outcome, elapsed: start.elapsed() }
+}
+
+/// Runs every registered solution against each input it has answers for in `dir`. Days with no recorded answers and
+/// no input file are skipped rather than reported as errors.
+pub fn verify_all(dir: &Path) -> Result<Vec<Check>, AnswersError> {
+    let answers = load_answers(dir)?;
+    let mut checks = Vec::new();
+    for &sol in SOLUTIONS {
+        let recorded = answers.iter().any(|a| a.day == sol.day());
+        for id in input_ids(&answers, sol.day()) {
+            let input = read_input(&dir.join(format!("{}.txt", id)));
+            if !recorded && matches!(input, Err(InputError::Missing(_))) {
+                continue;
+            }
+            for part in Part::BOTH {
+                checks.push(check(sol, part, &id, &input, &answers));
+            }
+        }
+    }
+    Ok(checks)
+}
+
+/// Test helper: checks one part of a day against the recorded answer for its usual input.
+#[cfg(test)]
+pub(crate) fn assert_answer(sol: &dyn Runner, part: Part) {
+    let dir = crate::util::input_dir();
+    let answers = load_answers(&dir).unwrap();
+    let id = default_input_id(sol.day());
+    let input = read_input(&dir.join(format!("{}.txt", id)));
+    match check(sol, part, &id, &input, &answers).outcome {
+        Outcome::Pass => {}
+        Outcome::Fail(actual) => assert_eq!(actual, expected(&answers, sol.day(), part, &id).unwrap()),
+        other => panic!("day {:02} part {}: {:?}", sol.day(), part, other)
+    }
+}
+
+mod parse {
+    use nom::branch::alt;
+    use nom::bytes::complete::{tag, take_while1};
+    use nom::character::complete::{newline, not_line_ending, space1, u32 as num};
+    use nom::combinator::{map, map_opt, opt, value};
+    use nom::IResult;
+    use nom::multi::separated_list1;
+    use nom::sequence::{pair, terminated, tuple};
+
+    use super::*;
+
+    fn unescape(text: &str) -> String {
+        let mut out = String::with_capacity(text.len());
+        let mut chars = text.chars();
+        while let Some(ch) = chars.next() {
+            match (ch, chars.clone().next()) {
+                ('\\', Some('n')) => { out.push('\n'); chars.next(); }
+                ('\\', Some('\\')) => { out.push('\\'); chars.next(); }
+                (ch, _) => out.push(ch)
+            }
+        }
+        out
+    }
+
+    fn part(input: &str) -> IResult<&str, Part> {
+        map_opt(num, Part::from_number)(input)
+    }
+
+    fn input_id(input: &str) -> IResult<&str, &str> {
+        take_while1(|ch: char| ch.is_alphanumeric() || ch == '-' || ch == '_')(input)
+    }
+
+    fn answer(input: &str) -> IResult<&str, Answer> {
+        map(tuple((terminated(num, space1),
+                   terminated(part, space1),
+                   terminated(input_id, space1),
+                   not_line_ending)),
+            |(day, part, id, text)| Answer {
+                day,
+                part,
+                input: id.to_owned(),
+                expected: unescape(text),
+            })(input)
+    }
+
+    fn comment(input: &str) -> IResult<&str, ()> {
+        value((), pair(tag("#"), not_line_ending))(input)
+    }
+
+    pub(crate) fn answers(input: &str) -> IResult<&str, Vec<Answer>> {
+        let line = opt(alt((map(answer, Some), value(None, comment))));
+        map(separated_list1(newline, line),
+            |lines| lines.into_iter().flatten().flatten().collect())(input)
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+
+    const TEST_ANSWERS: &str = "# comment\n01 1 day01 7\n\n13 2 small a\\nb\\\\\n";
+
+    #[test]
+    fn test_parse() {
+        let (_, answers) = parse::answers(TEST_ANSWERS).unwrap();
+        assert_eq!(answers, [
+            Answer { day: 1, part: Part::One, input: "day01".to_owned(), expected: "7".to_owned() },
+            Answer { day: 13, part: Part::Two, input: "small".to_owned(), expected: "a\nb\\".to_owned() },
+        ]);
+    }
+
+    #[test]
+    fn test_input_ids() {
+        let (_, answers) = parse::answers(TEST_ANSWERS).unwrap();
+        assert_eq!(input_ids(&answers, 13), ["small"]);
+        assert_eq!(input_ids(&answers, 2), ["day02"]);
+    }
+
+    #[test]
+    fn test_check() {
+        let (_, answers) = parse::answers(TEST_ANSWERS).unwrap();
+        let sol = crate::solution(1).unwrap();
+        let input = Ok("1\n2\n3".to_owned());
+
+        assert_eq!(check(sol, Part::One, "day01", &input, &[]).outcome, Outcome::Missing("2".to_owned()));
+        assert_eq!(check(sol, Part::One, "day01", &input, &answers).outcome, Outcome::Fail("2".to_owned()));
+        let bad = Ok("1\nx".to_owned());
+        assert!(matches!(check(sol, Part::One, "day01", &bad, &answers).outcome, Outcome::Error(_)));
+    }
+
+    #[test]
+    fn test_verify_skips_days_without_input() {
+        let dir = std::env::temp_dir().join(format!("advent2021-{}-verify", std::process::id()));
+        std::fs::create_dir_all(&dir).unwrap();
+        std::fs::write(dir.join(ANSWERS_FILE), "01 1 day01 2\n02 1 day02 5\n").unwrap();
+        std::fs::write(dir.join("day01.txt"), "1\n2\n3\n").unwrap();
+
+        // Day 2 has answers recorded, so its missing input is still an error; every other day is just skipped
+        let checks = verify_all(&dir).unwrap();
+        let outcomes: Vec<(u32, &Outcome)> = checks.iter().map(|c| (c.day, &c.outcome)).collect();
+        assert_eq!(outcomes.len(), 4);
+        assert_eq!(outcomes[0], (1, &Outcome::Pass));
+        assert_eq!(outcomes[1].0, 1);
+        assert!(outcomes[2..].iter().all(|&(day, outcome)| day == 2 && matches!(outcome, Outcome::Error(_))));
+    }
+}